@@ -55,7 +55,7 @@ fn main() {
         });
         scope.spawn(|_| loop {
             let ev = unsafe { mpv.wait_event(600.) };
-            if let Some(Ok(Event::EndFile(r))) = ev {
+            if let Some(Ok(Event::EndFile { reason: r, .. })) = ev {
                 println!("Exiting! Reason: {:?}", r);
                 break;
             } else if let Some(Ok(e)) = ev {
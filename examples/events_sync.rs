@@ -44,16 +44,23 @@ fn main() {
     // Create a crossbeam scope for convenience to use mpv in multiple threads.
     crossbeam::scope(|scope| {
         // Spin up 3 threads that observe different sets of `Event`s.
-        scope.spawn(|| {
+        scope.spawn(|_| {
             let iter = mpv
                 .observe_events(&[
-                    Event::FileLoaded,
-                    Event::StartFile,
+                    Event::FileLoaded {
+                        tracks: Vec::new(),
+                        duration: None,
+                        filename: String::new(),
+                    },
+                    Event::StartFile {
+                        playlist_entry_id: 0,
+                    },
                     Event::Seek,
                     Event::PlaybackRestart,
                     Event::EndFile {
-                        reason: mpv_end_file_reason::Eof,
+                        reason: EndFileReason::Eof,
                         error: None,
+                        playlist_entry_id: 0,
                     },
                 ])
                 .unwrap();
@@ -63,6 +70,7 @@ fn main() {
                 if let Some(&Event::EndFile {
                     reason: ref r,
                     error: ref e,
+                    ..
                 }) = vec.iter().find(|ev| {
                     if let Event::EndFile { .. } = **ev {
                         true
@@ -80,7 +88,7 @@ fn main() {
                 };
             }
         });
-        scope.spawn(|| {
+        scope.spawn(|_| {
             // Here the value of the property is irrelevant: only the name is used.
             let iter = mpv
                 .observe_events(&[
@@ -93,9 +101,9 @@ fn main() {
                 println!("properties: {:?}", vec);
             }
         });
-        scope.spawn(|| {
+        scope.spawn(|_| {
             let iter = mpv
-                .observe_events(&[Event::empty_logmessage(mpv_log_level::Info)])
+                .observe_events(&[Event::empty_logmessage(LogLevel::Info)])
                 .unwrap();
 
             for vec in iter {
@@ -114,5 +122,6 @@ fn main() {
 
         // Trigger `Event::EndFile` observed above to quit.
         mpv.playlist_next_force().unwrap();
-    });
+    })
+    .unwrap();
 }
@@ -1741,6 +1741,38 @@ fn bindgen_test_layout_mpv_event_hook() {
     );
 }
 #[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mpv_event_command {
+    /// Result data of the command. Note that success/failure is signaled
+    /// separately via mpv_event.error. This field is only for result data
+    /// in case of success. Most commands leave it at MPV_FORMAT_NONE. Set
+    /// to MPV_FORMAT_NONE on failure.
+    pub result: mpv_node,
+}
+#[test]
+fn bindgen_test_layout_mpv_event_command() {
+    assert_eq!(
+        ::std::mem::size_of::<mpv_event_command>(),
+        16usize,
+        concat!("Size of: ", stringify!(mpv_event_command))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<mpv_event_command>(),
+        8usize,
+        concat!("Alignment of ", stringify!(mpv_event_command))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<mpv_event_command>())).result as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(mpv_event_command),
+            "::",
+            stringify!(result)
+        )
+    );
+}
+#[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct mpv_event {
     /// One of mpv_event. Keep in mind that later ABI compatible releases might
@@ -2288,6 +2320,37 @@ extern "C" {
         callback_ctx: *mut ::std::os::raw::c_void,
     );
 }
+/// A new video frame must be rendered. mpv_render_context_render() must be
+/// called.
+pub const mpv_render_update_flag_MPV_RENDER_UPDATE_FRAME: mpv_render_update_flag = 1;
+/// Flags returned by mpv_render_context_update(). Each value represents a bit
+/// in the function's return value.
+pub type mpv_render_update_flag = u32;
+pub use self::mpv_render_update_flag as mpv_render_context_flag;
+extern "C" {
+    /// The API user is supposed to call this when the update callback was invoked
+    /// (like all mpv_render_* functions, this has to happen on the render thread,
+    /// and _not_ from the update callback itself).
+    ///
+    /// This is optional if MPV_RENDER_PARAM_ADVANCED_CONTROL was not set (default).
+    /// Otherwise, it's a hard requirement that this is called after each update
+    /// callback. If multiple update callback happened, and the function could not
+    /// be called sooner, it's OK to call it once after the last callback.
+    ///
+    /// If an update callback happens during or after this function, the function
+    /// must be called again at the soonest possible time.
+    ///
+    /// If MPV_RENDER_PARAM_ADVANCED_CONTROL was set, this will do additional work
+    /// such as allocating textures for the video decoder.
+    ///
+    /// @return a bitset of mpv_render_update_flag values (i.e. multiple flags are
+    /// combined with bitwise or). Typically, this will tell the API user
+    /// what should happen next. E.g. if the MPV_RENDER_UPDATE_FRAME flag is
+    /// set, mpv_render_context_render() should be called. If flags unknown
+    /// to the API user are set, or if the return value is 0, nothing needs
+    /// to be done.
+    pub fn mpv_render_context_update(ctx: *mut mpv_render_context) -> u64;
+}
 extern "C" {
     /// Render video.
     ///
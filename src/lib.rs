@@ -47,6 +47,8 @@ compile_error!(
 // - update constants in build.rs
 // - run tests and examples to test whether they still work
 
+use std::convert::TryFrom;
+use std::fmt;
 use std::os::raw as ctype;
 
 #[allow(missing_docs)]
@@ -63,13 +65,92 @@ mod wrapper;
 
 pub use crate::wrapper::*;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 /// A format mpv can use.
-pub use mpv_sys::mpv_format as MpvFormat;
+pub enum MpvFormat {
+    /// No data, e.g. of an unavailable property.
+    None,
+    /// A UTF-8 string.
+    String,
+    /// A UTF-8 string formatted for display, e.g. by `Mpv::get_property_osd`.
+    OsdString,
+    /// A boolean, `int` in C.
+    Flag,
+    /// A signed 64 bit integer.
+    Int64,
+    /// A 64 bit float.
+    Double,
+    /// An `mpv_node`, which can hold any of the other formats.
+    Node,
+    /// An array of `mpv_node`s, only used within nodes.
+    NodeArray,
+    /// A map of strings to `mpv_node`s, only used within nodes.
+    NodeMap,
+    /// Raw bytes, only used within nodes.
+    ByteArray,
+    /// A format added by a newer libmpv.
+    Unknown(i32),
+}
+
+impl MpvFormat {
+    pub(crate) fn from_raw(format: mpv_sys::mpv_format) -> MpvFormat {
+        MpvFormat::from(format as i32)
+    }
+
+    pub(crate) fn as_raw(self) -> mpv_sys::mpv_format {
+        i32::from(self) as _
+    }
+}
+
+impl From<i32> for MpvFormat {
+    fn from(format: i32) -> MpvFormat {
+        if format < 0 {
+            return MpvFormat::Unknown(format);
+        }
+        match format as mpv_sys::mpv_format {
+            mpv_format::None => MpvFormat::None,
+            mpv_format::String => MpvFormat::String,
+            mpv_format::OsdString => MpvFormat::OsdString,
+            mpv_format::Flag => MpvFormat::Flag,
+            mpv_format::Int64 => MpvFormat::Int64,
+            mpv_format::Double => MpvFormat::Double,
+            mpv_format::Node => MpvFormat::Node,
+            mpv_format::NodeArray => MpvFormat::NodeArray,
+            mpv_format::NodeMap => MpvFormat::NodeMap,
+            mpv_format::ByteArray => MpvFormat::ByteArray,
+            _ => MpvFormat::Unknown(format),
+        }
+    }
+}
+
+impl From<MpvFormat> for i32 {
+    fn from(format: MpvFormat) -> i32 {
+        (match format {
+            MpvFormat::None => mpv_format::None,
+            MpvFormat::String => mpv_format::String,
+            MpvFormat::OsdString => mpv_format::OsdString,
+            MpvFormat::Flag => mpv_format::Flag,
+            MpvFormat::Int64 => mpv_format::Int64,
+            MpvFormat::Double => mpv_format::Double,
+            MpvFormat::Node => mpv_format::Node,
+            MpvFormat::NodeArray => mpv_format::NodeArray,
+            MpvFormat::NodeMap => mpv_format::NodeMap,
+            MpvFormat::ByteArray => mpv_format::ByteArray,
+            MpvFormat::Unknown(format) => return format,
+        }) as i32
+    }
+}
+
+/// The raw values of `MpvFormat`.
 pub mod mpv_format {
     #![allow(missing_docs)]
+    pub use mpv_sys::mpv_format_MPV_FORMAT_BYTE_ARRAY as ByteArray;
     pub use mpv_sys::mpv_format_MPV_FORMAT_DOUBLE as Double;
     pub use mpv_sys::mpv_format_MPV_FORMAT_FLAG as Flag;
     pub use mpv_sys::mpv_format_MPV_FORMAT_INT64 as Int64;
+    pub use mpv_sys::mpv_format_MPV_FORMAT_NODE as Node;
+    pub use mpv_sys::mpv_format_MPV_FORMAT_NODE_ARRAY as NodeArray;
+    pub use mpv_sys::mpv_format_MPV_FORMAT_NODE_MAP as NodeMap;
     pub use mpv_sys::mpv_format_MPV_FORMAT_NONE as None;
     pub use mpv_sys::mpv_format_MPV_FORMAT_OSD_STRING as OsdString;
     pub use mpv_sys::mpv_format_MPV_FORMAT_STRING as String;
@@ -102,8 +183,86 @@ pub mod mpv_error {
     pub use mpv_sys::mpv_error_MPV_ERROR_VO_INIT_FAILED as VoInitFailed;
 }
 
-/// Log verbosity level.
-pub use mpv_sys::mpv_log_level as LogLevel;
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Log verbosity level, ordered from least to most verbose.
+pub enum LogLevel {
+    /// Disables log messages, never used for received messages.
+    None,
+    /// Critical or aborting errors.
+    Fatal,
+    /// Simple errors.
+    Error,
+    /// Possible problems.
+    Warn,
+    /// Informational messages.
+    Info,
+    /// Noisy informational messages, `v` in mpv.
+    Verbose,
+    /// Very noisy technical information.
+    Debug,
+    /// Extremely noisy.
+    Trace,
+}
+
+impl LogLevel {
+    // Newer libmpv versions may add levels, which are treated as the most verbose one.
+    pub(crate) fn from_raw(level: mpv_sys::mpv_log_level) -> LogLevel {
+        LogLevel::try_from(level as i32).unwrap_or(LogLevel::Trace)
+    }
+
+    pub(crate) fn as_raw(self) -> mpv_sys::mpv_log_level {
+        match self {
+            LogLevel::None => mpv_log_level::None,
+            LogLevel::Fatal => mpv_log_level::Fatal,
+            LogLevel::Error => mpv_log_level::Error,
+            LogLevel::Warn => mpv_log_level::Warn,
+            LogLevel::Info => mpv_log_level::Info,
+            LogLevel::Verbose => mpv_log_level::V,
+            LogLevel::Debug => mpv_log_level::Debug,
+            LogLevel::Trace => mpv_log_level::Trace,
+        }
+    }
+}
+
+/// Returns `Error::InvalidArgument` for values that are not one of `mpv_log_level`.
+impl TryFrom<i32> for LogLevel {
+    type Error = Error;
+
+    fn try_from(level: i32) -> Result<LogLevel> {
+        if level < 0 {
+            return Err(Error::InvalidArgument);
+        }
+        Ok(match level as mpv_sys::mpv_log_level {
+            mpv_log_level::None => LogLevel::None,
+            mpv_log_level::Fatal => LogLevel::Fatal,
+            mpv_log_level::Error => LogLevel::Error,
+            mpv_log_level::Warn => LogLevel::Warn,
+            mpv_log_level::Info => LogLevel::Info,
+            mpv_log_level::V => LogLevel::Verbose,
+            mpv_log_level::Debug => LogLevel::Debug,
+            mpv_log_level::Trace => LogLevel::Trace,
+            _ => return Err(Error::InvalidArgument),
+        })
+    }
+}
+
+/// Formats the level like mpv does, e.g. for the `msg-level` option.
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LogLevel::None => "no",
+            LogLevel::Fatal => "fatal",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Verbose => "v",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        })
+    }
+}
+
+/// The raw values of `LogLevel`.
 pub mod mpv_log_level {
     #![allow(missing_docs)]
     pub use mpv_sys::mpv_log_level_MPV_LOG_LEVEL_DEBUG as Debug;
@@ -116,8 +275,63 @@ pub mod mpv_log_level {
     pub use mpv_sys::mpv_log_level_MPV_LOG_LEVEL_WARN as Warn;
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 /// The reason a file stopped.
-pub use mpv_sys::mpv_end_file_reason as EndFileReason;
+pub enum EndFileReason {
+    /// The end of the file was reached, or initialization failed.
+    Eof,
+    /// Playback was stopped by an external action, e.g. a playlist command.
+    Stop,
+    /// Playback was stopped by the quit command, or the player shutting down.
+    Quit,
+    /// An error happened, the event carries the error itself.
+    Error,
+    /// The file was a playlist or similar, and was replaced by its entries.
+    Redirect,
+    /// A reason added by a newer libmpv.
+    Unknown(ctype::c_int),
+}
+
+impl EndFileReason {
+    pub(crate) fn from_raw(reason: ctype::c_int) -> EndFileReason {
+        EndFileReason::try_from(reason).unwrap_or(EndFileReason::Unknown(reason))
+    }
+}
+
+/// Returns `Error::InvalidArgument` for reasons that are not one of `mpv_end_file_reason`, which
+/// `EndFileReason::Unknown` holds when received from libmpv.
+impl TryFrom<i32> for EndFileReason {
+    type Error = Error;
+
+    fn try_from(reason: i32) -> Result<EndFileReason> {
+        if reason < 0 {
+            return Err(Error::InvalidArgument);
+        }
+        Ok(match reason as mpv_sys::mpv_end_file_reason {
+            mpv_end_file_reason::Eof => EndFileReason::Eof,
+            mpv_end_file_reason::Stop => EndFileReason::Stop,
+            mpv_end_file_reason::Quit => EndFileReason::Quit,
+            mpv_end_file_reason::Error => EndFileReason::Error,
+            mpv_end_file_reason::Redirect => EndFileReason::Redirect,
+            _ => return Err(Error::InvalidArgument),
+        })
+    }
+}
+
+/// Formats the reason like the `end-file` event of mpv scripts, e.g. `eof`.
+impl fmt::Display for EndFileReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            EndFileReason::Eof => "eof",
+            EndFileReason::Stop => "stop",
+            EndFileReason::Quit => "quit",
+            EndFileReason::Error => "error",
+            EndFileReason::Redirect => "redirect",
+            EndFileReason::Unknown(_) => "unknown",
+        })
+    }
+}
+
 pub mod mpv_end_file_reason {
     #![allow(missing_docs)]
     pub use mpv_sys::mpv_end_file_reason_MPV_END_FILE_REASON_EOF as Eof;
@@ -30,10 +30,10 @@ fn properties() {
     mpv.set_property("ytdl-format", "best[width<240]").unwrap();
     mpv.set_property("sub-gauss", 0.6).unwrap();
 
-    assert_eq!(0i64, mpv.get_property("volume").unwrap());
+    assert_eq!(0i64, mpv.get_property::<i64>("volume").unwrap());
     let vo: MpvStr = mpv.get_property("vo").unwrap();
     assert_eq!("null", &*vo);
-    assert_eq!(true, mpv.get_property("ytdl").unwrap());
+    assert_eq!(true, mpv.get_property::<bool>("ytdl").unwrap());
     let subg: f64 = mpv.get_property("sub-gauss").unwrap();
     assert_eq!(
         0.6,
@@ -55,6 +55,1681 @@ fn properties() {
     );
 }
 
+#[test]
+fn sub_pos() {
+    let mpv = Mpv::new().unwrap();
+
+    assert_eq!(Err(Error::InvalidArgument), mpv.set_sub_pos(151));
+
+    mpv.set_sub_pos(150).unwrap();
+    assert_eq!(150i64, mpv.get_property::<i64>("sub-pos").unwrap());
+    mpv.set_sub_pos(0).unwrap();
+    assert_eq!(0i64, mpv.get_property::<i64>("sub-pos").unwrap());
+
+    mpv.set_sub_margin_y(40).unwrap();
+    assert_eq!(40i64, mpv.get_property::<i64>("sub-margin-y").unwrap());
+    mpv.set_sub_margin_x(30).unwrap();
+    assert_eq!(30i64, mpv.get_property::<i64>("sub-margin-x").unwrap());
+}
+
+#[test]
+fn ab_loop() {
+    let mpv = Mpv::new().unwrap();
+
+    assert_eq!(Err(Error::InvalidArgument), mpv.ab_loop_set(3., 1.5));
+    assert_eq!(Err(Error::InvalidArgument), mpv.ab_loop_set(-1., 1.5));
+    assert_eq!(Err(Error::InvalidArgument), mpv.ab_loop_set(f64::NAN, 1.5));
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        mpv.ab_loop_set(1.5, f64::INFINITY)
+    );
+
+    mpv.ab_loop_set(1.5, 3.).unwrap();
+    assert_eq!(1.5f64, mpv.get_property::<f64>("ab-loop-a").unwrap());
+    assert_eq!(3f64, mpv.get_property::<f64>("ab-loop-b").unwrap());
+
+    mpv.ab_loop_clear().unwrap();
+    let a: MpvStr = mpv.get_property("ab-loop-a").unwrap();
+    assert_eq!("no", &*a);
+
+    mpv.set_ab_loop(Some((1.5, 3.))).unwrap();
+    assert_eq!(Some((1.5, 3.)), mpv.ab_loop().unwrap());
+    mpv.set_ab_loop(None).unwrap();
+    assert_eq!(None, mpv.ab_loop().unwrap());
+}
+
+#[test]
+fn frame_step() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("vo", "null").unwrap();
+    mpv.set_property("ao", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file(
+        "av://lavfi:testsrc=size=320x240:duration=10",
+        FileState::Replace,
+        &[],
+    )
+    .unwrap();
+    mpv.wait_for_property("estimated-frame-number", 0i64, Duration::from_secs(10))
+        .unwrap();
+
+    let fps: f64 = mpv.get_property("container-fps").unwrap();
+    let before: f64 = mpv.get_property("time-pos").unwrap();
+    let frame = mpv.current_frame().unwrap();
+    mpv.frame_step().unwrap();
+    mpv.wait_for_property("estimated-frame-number", frame + 1, Duration::from_secs(10))
+        .unwrap();
+    let after: f64 = mpv.get_property("time-pos").unwrap();
+
+    assert!(mpv.get_property::<bool>("pause").unwrap());
+    assert!((after - before - 1. / fps).abs() < 0.5 / fps);
+    assert_eq!(frame + 1, mpv.current_frame().unwrap());
+    assert!(mpv.total_frames().unwrap().unwrap() > frame);
+}
+
+#[test]
+fn audio_devices() {
+    let mpv = Mpv::new().unwrap();
+    let devices = mpv.audio_devices().unwrap();
+    assert!(devices.iter().any(|d| d.name == "auto"));
+
+    mpv.set_audio_device("auto").unwrap();
+    assert_eq!("auto", mpv.audio_device().unwrap());
+}
+
+#[test]
+fn audio_capture() {
+    let mpv = Mpv::new().unwrap();
+    let mut capture = mpv.enable_audio_capture().unwrap();
+    mpv.set_property("keep-open", "yes").unwrap();
+    mpv.load_file("av://lavfi:sine=d=0.5", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_property("eof-reached", true, Duration::from_secs(10))
+        .unwrap();
+
+    let mut buf = vec![0.; 4096];
+    let read = capture.read_samples(&mut buf).unwrap();
+    assert!(read > 0);
+    assert!(buf[..read].iter().all(|s| (-1. ..=1.).contains(s)));
+    assert!(buf[..read].iter().any(|&s| s != 0.));
+}
+
+#[test]
+fn command_blocking() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("volume", 42).unwrap();
+
+    let node = mpv
+        .command_blocking(&["expand-text", "${volume}"], Duration::MAX)
+        .unwrap();
+    assert_eq!(Some("42"), node.as_str());
+    assert_eq!(
+        Err(Error::Raw(mpv_error::InvalidParameter)),
+        mpv.command_blocking(&["no-such-command"], Duration::from_secs(3))
+    );
+}
+
+#[test]
+fn profiles() {
+    let mpv = Mpv::new().unwrap();
+    assert!(mpv
+        .profile_list()
+        .unwrap()
+        .iter()
+        .any(|p| p == "pseudo-gui"));
+
+    mpv.apply_profile("pseudo-gui").unwrap();
+    mpv.set_property("volume", 42).unwrap();
+    mpv.reset_option("volume").unwrap();
+    assert_eq!(100i64, mpv.get_property::<i64>("volume").unwrap());
+    mpv.set_property("alang", "en,de").unwrap();
+    mpv.reset_option("alang").unwrap();
+    assert_eq!("", mpv.get_property::<String>("alang").unwrap());
+}
+
+#[test]
+fn playback_state() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("volume", 42).unwrap();
+
+    let state = mpv.playback_state().unwrap();
+    assert_eq!(42., state.volume);
+    assert_eq!(None, state.time_pos);
+    assert_eq!(None, state.duration);
+}
+
+#[test]
+fn property_enum() {
+    use std::convert::TryFrom;
+
+    #[derive(Debug, PartialEq)]
+    enum Rotation {
+        None,
+        Right,
+    }
+
+    impl TryFrom<i64> for Rotation {
+        type Error = ();
+
+        fn try_from(v: i64) -> std::result::Result<Rotation, ()> {
+            match v {
+                0 => Ok(Rotation::None),
+                90 => Ok(Rotation::Right),
+                _ => Err(()),
+            }
+        }
+    }
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("video-rotate", 90i64).unwrap();
+    assert_eq!(
+        Rotation::Right,
+        mpv.get_property_enum("video-rotate").unwrap()
+    );
+    mpv.set_property("video-rotate", 180i64).unwrap();
+    assert_eq!(
+        Err(Error::Raw(mpv_error::PropertyFormat)),
+        mpv.get_property_enum::<Rotation>("video-rotate")
+    );
+}
+
+#[test]
+fn cache_state() {
+    let mpv = Mpv::new().unwrap();
+    assert_eq!(
+        CacheState {
+            buffering_percent: None,
+            paused_for_cache: false,
+            cache_duration: None,
+        },
+        mpv.cache_state().unwrap()
+    );
+    assert_eq!(
+        Err(Error::Raw(mpv_error::PropertyUnavailable)),
+        mpv.cache_status()
+    );
+}
+
+#[test]
+fn metadata() {
+    let mpv = Mpv::new().unwrap();
+    assert!(mpv.metadata().unwrap().is_empty());
+
+    let mut tags = std::collections::HashMap::new();
+    tags.insert("TITLE".to_owned(), "foo".to_owned());
+    let tags = Metadata::from(tags);
+    assert_eq!(Some("foo"), tags.title());
+    assert_eq!(None, tags.artist());
+}
+
+#[test]
+fn file_metadata() {
+    // One second of silent 8 kHz mono audio, titled through a RIFF INFO chunk.
+    let info = b"INFOINAM\x04\0\0\0foo\0";
+    let samples = vec![0u8; 16000];
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(4 + 24 + 8 + info.len() as u32 + 8 + 16000).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt \x10\0\0\0\x01\0\x01\0");
+    wav.extend_from_slice(&8000u32.to_le_bytes());
+    wav.extend_from_slice(&16000u32.to_le_bytes());
+    wav.extend_from_slice(b"\x02\0\x10\0LIST");
+    wav.extend_from_slice(&(info.len() as u32).to_le_bytes());
+    wav.extend_from_slice(info);
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&samples);
+    std::fs::write("/tmp/mpv-rs-metadata.wav", wav).unwrap();
+    std::fs::write(
+        "/tmp/mpv-rs-chapters.txt",
+        ";FFMETADATA1\n\
+         [CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=500\ntitle=intro\n\
+         [CHAPTER]\nTIMEBASE=1/1000\nSTART=500\nEND=1000\ntitle=outro\n",
+    )
+    .unwrap();
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("ao", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.set_property("chapters-file", "/tmp/mpv-rs-chapters.txt")
+        .unwrap();
+    mpv.load_file("/tmp/mpv-rs-metadata.wav", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_property("chapters", 2i64, Duration::from_secs(10))
+        .unwrap();
+
+    assert_eq!(Some("foo"), Metadata::from(mpv.metadata().unwrap()).title());
+    let chapter = mpv.chapter_metadata(1).unwrap();
+    assert_eq!(Some("outro"), chapter.get("title").map(|t| &t[..]));
+}
+
+#[test]
+fn property_osd() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("pause", true).unwrap();
+    assert_eq!("yes", mpv.get_property_osd("pause").unwrap());
+}
+
+#[test]
+fn property_string() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("volume", 42).unwrap();
+    assert_eq!("42.000000", mpv.get_property_string("volume").unwrap());
+}
+
+#[test]
+fn encode_builder() {
+    let mpv = EncodeBuilder::new("/tmp/mpv-rs-test.mkv")
+        .unwrap()
+        .video_codec("libx264")
+        .unwrap()
+        .video_codec_options(&[("crf", "23")])
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(
+        "/tmp/mpv-rs-test.mkv",
+        mpv.get_property_string("o").unwrap()
+    );
+    assert_eq!("libx264", mpv.get_property_string("ovc").unwrap());
+}
+
+#[test]
+fn encode() {
+    let output = "/tmp/mpv-rs-encode.mkv";
+    let _ = std::fs::remove_file(output);
+    let mpv = EncodeBuilder::new(output).unwrap().build().unwrap();
+    mpv.load_file("av://lavfi:testsrc=d=0.1", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_encode().unwrap();
+    // The file is finished once the core is destroyed.
+    drop(mpv);
+    assert!(std::fs::metadata(output).unwrap().len() > 0);
+}
+
+#[test]
+fn pause_controls() {
+    let mpv = Mpv::new().unwrap();
+    mpv.pause().unwrap();
+    mpv.toggle_pause().unwrap();
+    assert!(!mpv.get_property::<bool>("pause").unwrap());
+    mpv.toggle_pause().unwrap();
+    assert!(mpv.get_property::<bool>("pause").unwrap());
+    mpv.play().unwrap();
+    assert!(!mpv.get_property::<bool>("pause").unwrap());
+    mpv.stop(true).unwrap();
+}
+
+#[test]
+fn speed() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_speed(1.5).unwrap();
+    assert_eq!(1.5, mpv.speed().unwrap());
+    mpv.adjust_speed(0.25).unwrap();
+    assert_eq!(1.75, mpv.speed().unwrap());
+    assert_eq!(Err(Error::InvalidArgument), mpv.set_speed(0.));
+    assert_eq!(Err(Error::InvalidArgument), mpv.set_speed(-1.));
+    assert_eq!(Err(Error::InvalidArgument), mpv.set_speed(f64::INFINITY));
+    assert_eq!(Err(Error::InvalidArgument), mpv.set_speed(f64::NAN));
+    assert_eq!(Err(Error::InvalidArgument), mpv.adjust_speed(f64::NAN));
+    assert_eq!(Err(Error::InvalidArgument), mpv.adjust_speed(f64::INFINITY));
+    assert_eq!(1.75, mpv.speed().unwrap());
+    mpv.reset_speed().unwrap();
+    assert_eq!(1., mpv.speed().unwrap());
+}
+
+#[test]
+fn clone() {
+    let mpv = Mpv::new().unwrap();
+    let client = mpv.clone();
+    client.set_property("volume", 42i64).unwrap();
+    drop(client);
+    assert_eq!(42i64, mpv.get_property::<i64>("volume").unwrap());
+}
+
+#[test]
+fn scripts() {
+    let mpv = Mpv::new().unwrap();
+    mpv.send_script_message("mpv-rs-test", &["foo", "bar baz"])
+        .unwrap();
+    assert!(mpv
+        .run_script(std::path::Path::new("/nonexistent/script.lua"))
+        .is_err());
+}
+
+#[test]
+fn batch() {
+    let mpv = Mpv::new().unwrap();
+    mpv.batch()
+        .command(&["set", "pause", "yes"])
+        .command(&["set", "volume", "42"])
+        .run()
+        .unwrap();
+    assert!(mpv.get_property::<bool>("pause").unwrap());
+
+    match mpv
+        .batch()
+        .command(&["set", "volume", "21"])
+        .command(&["no-such-command"])
+        .command(&["set", "volume", "0"])
+        .run()
+    {
+        Err(Error::Batch { index: 1, .. }) => {}
+        ret => panic!("expected the second command to fail, got {:?}", ret),
+    }
+    assert_eq!(21i64, mpv.get_property::<i64>("volume").unwrap());
+}
+
+#[test]
+fn command_string() {
+    let mpv = Mpv::new().unwrap();
+    mpv.command_string("set pause yes").unwrap();
+    assert!(mpv.get_property::<bool>("pause").unwrap());
+    mpv.command_string("set pause no; set volume 42").unwrap();
+    assert!(!mpv.get_property::<bool>("pause").unwrap());
+    assert_eq!(42i64, mpv.get_property::<i64>("volume").unwrap());
+}
+
+#[test]
+fn wait_for_property() {
+    let mpv = Mpv::new().unwrap();
+    mpv.wait_for_property("pause", false, Duration::from_secs(0))
+        .unwrap();
+    assert_eq!(
+        Err(Error::Timeout),
+        mpv.wait_for_property("pause", true, Duration::from_millis(50))
+    );
+}
+
+#[test]
+fn eof_and_idle() {
+    let mpv = Mpv::new().unwrap();
+    assert!(mpv.is_idle().unwrap());
+    assert!(!mpv.is_eof().unwrap());
+
+    mpv.set_property("keep-open", "yes").unwrap();
+    mpv.load_file("av://lavfi:anullsrc=d=0.1", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_property("eof-reached", true, Duration::from_secs(10))
+        .unwrap();
+    assert!(mpv.is_eof().unwrap());
+    assert!(!mpv.is_idle().unwrap());
+}
+
+#[test]
+fn property_names() {
+    let mpv = Mpv::new().unwrap();
+    let properties = mpv.property_names().unwrap();
+    assert!(properties.iter().any(|name| name == "pause"));
+    assert!(properties.iter().any(|name| name == "volume"));
+    let options = mpv.option_names().unwrap();
+    assert!(options.iter().any(|name| name == "pause"));
+}
+
+#[test]
+fn video_params() {
+    let mpv = Mpv::new().unwrap();
+    assert_eq!(None, mpv.video_params().unwrap());
+
+    mpv.set_property("vo", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file("av://lavfi:testsrc=size=320x240", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_property("video-params/w", 320i64, Duration::from_secs(10))
+        .unwrap();
+    let params = mpv.video_params().unwrap().unwrap();
+    assert_eq!((320, 240), (params.width, params.height));
+    assert_eq!(0, params.rotate);
+}
+
+#[test]
+fn add_subtitle() {
+    let path = "/tmp/mpv-rs-test.srt";
+    std::fs::write(path, "1\n00:00:00,000 --> 00:00:05,000\nfoo\n").unwrap();
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("vo", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file("av://lavfi:testsrc=size=320x240", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_property("video-params/w", 320i64, Duration::from_secs(10))
+        .unwrap();
+
+    mpv.add_subtitle(path, SubtitleFlag::Select, None, Some("eng"))
+        .unwrap();
+    let tracks = mpv.tracks().unwrap();
+    let sub = tracks.iter().find(|track| track.kind == "sub").unwrap();
+    assert_eq!(Some("eng"), sub.lang.as_deref());
+    // The placeholder for the missing title isn't used as one.
+    assert_ne!(Some(""), sub.title.as_deref());
+    assert!(sub.external);
+    assert!(sub.selected);
+
+    // The only subtitle is followed by none.
+    mpv.cycle_subtitle().unwrap();
+    assert_eq!("no", mpv.get_property_string("sid").unwrap());
+}
+
+#[test]
+fn video_geometry() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("vo", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file("av://lavfi:testsrc=size=320x240", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_property("video-params/w", 320i64, Duration::from_secs(10))
+        .unwrap();
+    let geometry = mpv.video_geometry().unwrap();
+    assert_eq!((320, 240), (geometry.width, geometry.height));
+    assert_eq!((320, 240), (geometry.dwidth, geometry.dheight));
+    assert!((geometry.aspect - 4. / 3.).abs() < 0.01);
+
+    for &aspect in &[-0.5, -1., f64::NAN, f64::INFINITY] {
+        assert_eq!(Err(Error::InvalidArgument), mpv.set_video_aspect(aspect));
+    }
+    mpv.set_video_aspect(2.).unwrap();
+    assert_eq!(
+        2.,
+        mpv.get_property::<f64>("video-aspect-override").unwrap()
+    );
+    mpv.reset_video_aspect().unwrap();
+    assert_eq!(
+        -1.,
+        mpv.get_property::<f64>("video-aspect-override").unwrap()
+    );
+}
+
+#[test]
+fn key_value_list() {
+    assert_eq!(
+        "start=30,aid=2",
+        crate::wrapper::key_value_list(&[("start", "30"), ("aid", "2")]).unwrap()
+    );
+    assert_eq!(
+        "sub-files=%7%a.s,b.s,title=%4%100%",
+        crate::wrapper::key_value_list(&[("sub-files", "a.s,b.s"), ("title", "100%")]).unwrap()
+    );
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        crate::wrapper::key_value_list(&[("a,b", "c")])
+    );
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("idle", "yes").unwrap();
+    mpv.load_file(
+        "av://lavfi:anullsrc",
+        FileState::AppendPlay,
+        &[("force-media-title", "a, b")],
+    )
+    .unwrap();
+    mpv.wait_for_property("media-title", "a, b".to_owned(), Duration::from_secs(10))
+        .unwrap();
+}
+
+#[test]
+fn node_visit() {
+    #[derive(Default)]
+    struct Ints(Vec<i64>);
+
+    impl NodeVisitor for Ints {
+        fn visit_i64(&mut self, v: i64) {
+            self.0.push(v);
+        }
+
+        fn map_key(&mut self, key: &str) -> bool {
+            key != "skip"
+        }
+    }
+
+    let mut map = std::collections::HashMap::new();
+    map.insert("keep".to_owned(), MpvNode::Int64(1));
+    map.insert("skip".to_owned(), MpvNode::Int64(2));
+    let node = MpvNode::Array(vec![MpvNode::Map(map), MpvNode::Int64(3)]);
+    let mut ints = Ints::default();
+    node.visit(&mut ints);
+    assert_eq!(vec![1, 3], ints.0);
+
+    let mpv = Mpv::new().unwrap();
+    let mut ints = Ints::default();
+    mpv.get_property_visit("playlist-count", &mut ints).unwrap();
+    assert_eq!(vec![0], ints.0);
+}
+
+#[test]
+fn playlist_pos() {
+    let mpv = Mpv::new().unwrap();
+    assert_eq!(None, mpv.playlist_pos().unwrap());
+    assert_eq!(Err(Error::InvalidArgument), mpv.refresh());
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        mpv.stream_dump(std::path::Path::new("/tmp/dump"))
+    );
+}
+
+#[test]
+fn playlist_next_prev() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("ao", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file("av://lavfi:anullsrc=d=10", FileState::Replace, &[])
+        .unwrap();
+    mpv.load_file("av://lavfi:sine=d=10", FileState::Append, &[])
+        .unwrap();
+    mpv.wait_for_property("playlist-pos", 0i64, Duration::from_secs(10))
+        .unwrap();
+
+    mpv.playlist_next(false).unwrap();
+    mpv.wait_for_property("playlist-pos", 1i64, Duration::from_secs(10))
+        .unwrap();
+    assert_eq!(Some(1), mpv.playlist_pos().unwrap());
+
+    mpv.playlist_prev(true).unwrap();
+    mpv.wait_for_property("playlist-pos", 0i64, Duration::from_secs(10))
+        .unwrap();
+    assert_eq!(Some(0), mpv.playlist_pos().unwrap());
+
+    // There is no previous entry, so playback stops.
+    mpv.playlist_prev(true).unwrap();
+    mpv.wait_for_property("playlist-pos", -1i64, Duration::from_secs(10))
+        .unwrap();
+    assert_eq!(None, mpv.playlist_pos().unwrap());
+}
+
+#[test]
+fn stream_dump_keeps_playlist() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file("av://lavfi:anullsrc=d=10", FileState::Replace, &[])
+        .unwrap();
+    mpv.load_file("av://lavfi:sine=d=10", FileState::Append, &[])
+        .unwrap();
+    mpv.wait_for_property(
+        "path",
+        "av://lavfi:anullsrc=d=10".to_owned(),
+        Duration::from_secs(10),
+    )
+    .unwrap();
+
+    mpv.stream_dump(std::path::Path::new("/tmp/mpv-rs-dump"))
+        .unwrap();
+    assert_eq!(3, mpv.get_property::<i64>("playlist-count").unwrap());
+    assert_eq!(Some(1), mpv.playlist_pos().unwrap());
+    for (i, file) in [
+        "av://lavfi:anullsrc=d=10",
+        "av://lavfi:anullsrc=d=10",
+        "av://lavfi:sine=d=10",
+    ]
+    .iter()
+    .enumerate()
+    {
+        assert_eq!(
+            *file,
+            mpv.get_property::<String>(&format!("playlist/{}/filename", i))
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn property_commands() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("volume", 50i64).unwrap();
+    mpv.add_property("volume", 5.5).unwrap();
+    assert_eq!(55.5, mpv.get_property::<f64>("volume").unwrap());
+    mpv.multiply_property("volume", 0.5).unwrap();
+    assert_eq!(27.75, mpv.get_property::<f64>("volume").unwrap());
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        mpv.add_property("volume", f64::NAN)
+    );
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        mpv.add_property("volume", f64::INFINITY)
+    );
+    for &factor in &[0., -2., f64::NAN, f64::INFINITY] {
+        assert_eq!(
+            Err(Error::InvalidArgument),
+            mpv.multiply_property("volume", factor)
+        );
+    }
+    assert_eq!(27.75, mpv.get_property::<f64>("volume").unwrap());
+
+    mpv.set_property("pause", false).unwrap();
+    mpv.cycle_property("pause", CycleDirection::Down).unwrap();
+    assert!(mpv.get_property::<bool>("pause").unwrap());
+}
+
+#[test]
+fn tracks() {
+    use crate::wrapper::FileLoadedInfo;
+
+    let mpv = Mpv::new().unwrap();
+    assert!(mpv.tracks().unwrap().is_empty());
+
+    let source = "av://lavfi:testsrc=d=5[out0];sine=d=5[out1]";
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file(source, FileState::Replace, &[]).unwrap();
+    mpv.wait_for_property("track-list/count", 2i64, Duration::from_secs(10))
+        .unwrap();
+
+    let info = FileLoadedInfo::from_ctx(mpv.ctx);
+    let kinds = info
+        .tracks
+        .iter()
+        .map(|track| (track.kind.as_str(), track.id))
+        .collect::<Vec<_>>();
+    assert!(kinds.contains(&("video", 1)));
+    assert!(kinds.contains(&("audio", 1)));
+    assert_eq!(mpv.tracks().unwrap(), info.tracks);
+    assert_eq!(mpv.get_property::<f64>("duration").ok(), info.duration);
+    assert_eq!(
+        mpv.get_property::<String>("filename").unwrap(),
+        info.filename
+    );
+    assert!(info.filename.ends_with("sine=d=5[out1]"));
+}
+
+#[test]
+fn recording() {
+    let mpv = Mpv::new().unwrap();
+    assert!(!mpv.is_recording().unwrap());
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        mpv.start_recording(std::path::Path::new("/tmp/record.mkv"))
+    );
+    mpv.stop_recording().unwrap();
+    assert!(!mpv.is_recording().unwrap());
+}
+
+#[test]
+fn mpv_format() {
+    for &format in &[
+        MpvFormat::None,
+        MpvFormat::String,
+        MpvFormat::OsdString,
+        MpvFormat::Flag,
+        MpvFormat::Int64,
+        MpvFormat::Double,
+        MpvFormat::Node,
+        MpvFormat::NodeArray,
+        MpvFormat::NodeMap,
+        MpvFormat::ByteArray,
+        MpvFormat::Unknown(42),
+    ] {
+        assert_eq!(format, MpvFormat::from(i32::from(format)));
+    }
+    assert_eq!(MpvFormat::Int64, MpvFormat::from(mpv_format::Int64 as i32));
+    assert_eq!(MpvFormat::Unknown(-1), MpvFormat::from(-1));
+}
+
+#[test]
+fn log_level() {
+    use std::convert::TryFrom;
+
+    assert_eq!(Ok(LogLevel::Verbose), LogLevel::try_from(50));
+    assert_eq!(Err(Error::InvalidArgument), LogLevel::try_from(-1));
+    assert_eq!(Err(Error::InvalidArgument), LogLevel::try_from(45));
+    assert!(LogLevel::Warn < LogLevel::Debug);
+    assert_eq!("v", LogLevel::Verbose.to_string());
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_log_level(Some(LogLevel::Warn)).unwrap();
+}
+
+#[cfg(feature = "render")]
+#[cfg_attr(feature = "render", test)]
+fn render_update_flags() {
+    use crate::render::UpdateFlags;
+
+    assert!(UpdateFlags::FRAME.contains(UpdateFlags::FRAME));
+    assert!(!UpdateFlags::FRAME.is_empty());
+    assert_eq!(1, UpdateFlags::FRAME.bits());
+}
+
+#[cfg(feature = "render")]
+#[cfg_attr(feature = "render", test)]
+fn software_render() {
+    use crate::render::PixelFormat;
+
+    let mpv = Mpv::new().unwrap();
+    let mut ctx = mpv
+        .create_software_render_context(PixelFormat::Rgba32)
+        .unwrap();
+    let mut buf = vec![0; 16 * 16 * 4];
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        ctx.render(16 * 4, &mut buf, 16, 32)
+    );
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        ctx.render(15 * 4, &mut buf, 16, 16)
+    );
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        ctx.render(usize::MAX, &mut buf, 16, 16)
+    );
+    ctx.set_pixel_format(PixelFormat::Bgrx32);
+    assert_eq!(PixelFormat::Bgrx32, ctx.pixel_format());
+    ctx.render(16 * 4, &mut buf, 16, 16).unwrap();
+}
+
+#[test]
+fn grab_frame_rgba() {
+    let mpv = Mpv::new().unwrap();
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        mpv.grab_frame_rgba(0, 16).map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::Raw(mpv_error::NothingToPlay)),
+        mpv.grab_frame_rgba(16, 16).map(|_| ())
+    );
+
+    mpv.set_property("vo", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file("av://lavfi:testsrc=size=320x240", FileState::Replace, &[])
+        .unwrap();
+    mpv.wait_for_property("video-params/w", 320i64, Duration::from_secs(10))
+        .unwrap();
+    let frame = mpv.grab_frame_rgba(16, 8).unwrap();
+    assert_eq!(16 * 8 * 4, frame.len());
+    assert!(frame.chunks_exact(4).all(|px| px[3] == 0xff));
+}
+
+#[cfg(feature = "serde_json")]
+#[cfg_attr(feature = "serde_json", test)]
+fn node_json() {
+    use std::convert::TryFrom;
+
+    let json = serde_json::json!({
+        "name": "foo",
+        "flags": [true, null],
+        "num": 42,
+        "ratio": 0.5,
+    });
+    let node = MpvNode::try_from(json.clone()).unwrap();
+    let map = node.as_map().unwrap();
+    assert_eq!(Some("foo"), map["name"].as_str());
+    assert_eq!(
+        Some(&[MpvNode::Flag(true), MpvNode::None][..]),
+        map["flags"].as_array()
+    );
+    assert_eq!(Some(42), map["num"].as_i64());
+    assert_eq!(MpvNode::Double(0.5), map["ratio"]);
+    assert_eq!(json, serde_json::Value::from(node));
+}
+
+#[test]
+fn end_file_reason() {
+    use std::convert::TryFrom;
+
+    for &(raw, reason, name) in &[
+        (mpv_end_file_reason::Eof, EndFileReason::Eof, "eof"),
+        (mpv_end_file_reason::Stop, EndFileReason::Stop, "stop"),
+        (mpv_end_file_reason::Quit, EndFileReason::Quit, "quit"),
+        (mpv_end_file_reason::Error, EndFileReason::Error, "error"),
+        (
+            mpv_end_file_reason::Redirect,
+            EndFileReason::Redirect,
+            "redirect",
+        ),
+    ] {
+        assert_eq!(reason, EndFileReason::from_raw(raw as _));
+        assert_eq!(Ok(reason), EndFileReason::try_from(raw as i32));
+        assert_eq!(name, reason.to_string());
+    }
+    assert_eq!(EndFileReason::Unknown(42), EndFileReason::from_raw(42));
+    assert_eq!(EndFileReason::Unknown(-1), EndFileReason::from_raw(-1));
+    assert_eq!(Err(Error::InvalidArgument), EndFileReason::try_from(42));
+    assert_eq!(Err(Error::InvalidArgument), EndFileReason::try_from(-1));
+}
+
+#[test]
+fn time_util() {
+    use crate::util::{format_time, parse_time};
+
+    assert_eq!("00:00", format_time(0.));
+    assert_eq!("01:23", format_time(83.9));
+    assert_eq!("1:00:05", format_time(3605.));
+    assert_eq!("-00:05", format_time(-5.5));
+    assert_eq!("--:--", format_time(f64::NAN));
+
+    assert_eq!(Some(83.), parse_time("1:23"));
+    assert_eq!(Some(3723.5), parse_time("1:02:03.5"));
+    assert_eq!(Some(-5.5), parse_time("-0:05.5"));
+    assert_eq!(Some(90.), parse_time("90"));
+    for time in &[
+        "", ":", "1:60", "1:60:00", "1::2", "a", "1:2:3:4", "+1", "-",
+    ] {
+        assert_eq!(None, parse_time(time));
+    }
+    // The hours overflow when converted to minutes.
+    assert_eq!(None, parse_time("999999999999999999:00:00"));
+
+    for &seconds in &[0., 59., 83., 3605., -42.] {
+        assert_eq!(Some(seconds), parse_time(&format_time(seconds)));
+    }
+}
+
+#[test]
+fn geometry() {
+    for geom in &["640x480", "640x480+10+20", "+10+20"] {
+        assert_eq!(*geom, geom.parse::<Geometry>().unwrap().to_string());
+    }
+    for geom in &[
+        "",
+        "640",
+        "640x",
+        "x480",
+        "640x480+10",
+        "+10+",
+        "640x480-10-20",
+    ] {
+        assert_eq!(
+            Err(Error::InvalidGeometry(geom.to_string())),
+            geom.parse::<Geometry>()
+        );
+    }
+}
+
+#[test]
+fn expand_path() {
+    let mpv = Mpv::new().unwrap();
+    assert_eq!("/tmp/foo", mpv.expand_path("/tmp/foo").unwrap());
+    assert!(!mpv.expand_path("~~home/foo").unwrap().starts_with("~~"));
+}
+
+#[test]
+fn osd_overlay() {
+    let mpv = Mpv::new().unwrap();
+    mpv.osd_overlay(1, "{\\an7}Now playing", 0, 0).unwrap();
+    mpv.remove_osd_overlay(1).unwrap();
+    // Removing it again is a no-op, like in mpv.
+    mpv.remove_osd_overlay(1).unwrap();
+}
+
+#[test]
+fn watch_later() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_watch_later_directory(std::path::Path::new("/tmp/mpv-rs-watch-later"))
+        .unwrap();
+    assert_eq!(
+        "/tmp/mpv-rs-watch-later",
+        mpv.get_property_string("watch-later-directory").unwrap()
+    );
+}
+
+#[test]
+fn video_toggles() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_interpolation(true).unwrap();
+    assert!(mpv.interpolation().unwrap());
+    mpv.set_deinterlace(true).unwrap();
+    assert!(mpv.deinterlace().unwrap());
+    mpv.set_deinterlace(false).unwrap();
+    assert!(!mpv.deinterlace().unwrap());
+    mpv.set_video_sync_mode(VideoSyncMode::DisplayResample)
+        .unwrap();
+    assert_eq!("display-resample", mpv.video_sync_mode().unwrap());
+}
+
+#[test]
+fn filters() {
+    let mpv = Mpv::new().unwrap();
+    mpv.set_video_filters(FilterOp::Add, "lavfi-crop=100:100")
+        .unwrap();
+    let vf = mpv.get_property::<MpvNode>("vf").unwrap();
+    assert_eq!(1, vf.as_array().unwrap().len());
+    mpv.set_video_filters(FilterOp::Clear, "").unwrap();
+    let vf = mpv.get_property::<MpvNode>("vf").unwrap();
+    assert_eq!(0, vf.as_array().unwrap().len());
+    assert!(mpv.set_audio_filters(FilterOp::Set, "a\0b").is_err());
+}
+
+#[test]
+fn screenshot_raw() {
+    let mpv = Mpv::new().unwrap();
+    // There is no video to take a screenshot of.
+    assert!(mpv.screenshot_raw_rgba(false).is_err());
+
+    let node = |w: i64, stride: i64| {
+        let mut map = std::collections::HashMap::new();
+        map.insert("w".to_owned(), MpvNode::Int64(w));
+        map.insert("h".to_owned(), MpvNode::Int64(1));
+        map.insert("stride".to_owned(), MpvNode::Int64(stride));
+        map.insert("format".to_owned(), MpvNode::String("bgr0".to_owned()));
+        map.insert("data".to_owned(), MpvNode::ByteArray(vec![1, 2, 3, 0]));
+        MpvNode::Map(map)
+    };
+    assert_eq!(
+        vec![3, 2, 1, 0xff],
+        RawScreenshot::from_node(&node(1, 4)).unwrap().data
+    );
+    for &(w, stride) in &[(0, 4), (0, 0), (1, 0)] {
+        assert_eq!(
+            Err(Error::Raw(mpv_error::PropertyFormat)),
+            RawScreenshot::from_node(&node(w, stride))
+        );
+    }
+}
+
+#[test]
+fn bind_key() {
+    let mpv = Mpv::new().unwrap();
+    mpv.bind_key("Ctrl+x", "set volume 42").unwrap();
+    mpv.key_press("Ctrl+x").unwrap();
+    mpv.wait_for_property("volume", 42i64, Duration::MAX)
+        .unwrap();
+
+    mpv.unbind_key("Ctrl+x").unwrap();
+    assert_eq!(Err(Error::InvalidArgument), mpv.unbind_key("Ctrl+x"));
+}
+
+#[test]
+fn key_input() {
+    let mpv = Mpv::new().unwrap();
+    // input.conf calls `#` SHARP. Pressing it is passed as a single argument, so it's the key and
+    // not a comment.
+    mpv.bind_key("SHARP", "set volume 7").unwrap();
+    mpv.key_press("#").unwrap();
+    mpv.wait_for_property("volume", 7i64, Duration::from_secs(10))
+        .unwrap();
+
+    mpv.bind_key("a", "set volume 9").unwrap();
+    mpv.key_down("a").unwrap();
+    mpv.wait_for_property("volume", 9i64, Duration::from_secs(10))
+        .unwrap();
+    mpv.key_up("a").unwrap();
+
+    mpv.mouse_move(10, 20).unwrap();
+
+    // Not a key name, and not split into a second command either.
+    assert!(mpv.key_press("x; quit").is_err());
+    assert!(mpv.get_property::<bool>("pause").is_ok());
+}
+
+#[test]
+fn displays() {
+    let mpv = Mpv::new().unwrap();
+    assert_eq!(Vec::<DisplayInfo>::new(), mpv.displays().unwrap());
+    mpv.set_fullscreen_display(1).unwrap();
+    assert_eq!("1", mpv.get_property_string("fs-screen").unwrap());
+    mpv.set_border(false).unwrap();
+    assert!(!mpv.has_border().unwrap());
+
+    // The title is expanded when it's displayed, not when it's set.
+    mpv.set_window_title("${filename} - mpv-rs").unwrap();
+    assert_eq!(
+        "${filename} - mpv-rs",
+        mpv.get_property_string("title").unwrap()
+    );
+    mpv.set_ontop(true).unwrap();
+    assert!(mpv.get_property::<bool>("ontop").unwrap());
+    mpv.set_ontop(false).unwrap();
+    assert!(!mpv.get_property::<bool>("ontop").unwrap());
+}
+
+#[test]
+fn clipboard() {
+    let mpv = Mpv::new().unwrap();
+    // Headless builds may have no clipboard backend at all.
+    match mpv.clipboard_set("mpv-rs") {
+        Ok(()) => assert_eq!("mpv-rs", mpv.clipboard_get().unwrap()),
+        Err(e) => assert_eq!(Error::CommandFailed, e),
+    }
+}
+
+#[cfg(feature = "events_simple")]
+#[cfg_attr(feature = "events_simple", test)]
+fn set_properties() {
+    use crate::events::simple::PropertyData;
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_properties(&[
+        ("volume", PropertyData::Int64(42)),
+        ("sub-gauss", PropertyData::Double(0.5)),
+    ])
+    .unwrap();
+    assert_eq!(42i64, mpv.get_property::<i64>("volume").unwrap());
+
+    assert_eq!(
+        Err(Error::SetProperty {
+            name: "no-such-property".to_owned(),
+            error: std::rc::Rc::new(Error::Raw(mpv_error::PropertyNotFound)),
+        }),
+        mpv.set_properties(&[
+            ("volume", PropertyData::Int64(21)),
+            ("no-such-property", PropertyData::Flag(true)),
+            ("mute", PropertyData::Flag(true)),
+        ])
+    );
+    assert_eq!(21i64, mpv.get_property::<i64>("volume").unwrap());
+    assert!(!mpv.get_property::<bool>("mute").unwrap());
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn observe_token() {
+    use crate::events::sync::Event;
+
+    let mpv = Mpv::new().unwrap();
+    let token = mpv.observe_events(&[Event::Seek]).unwrap().into_token();
+    let iter = mpv.resume_events(token);
+    assert_eq!(0, iter.peek_pending());
+    assert!(format!("{:?}", iter).contains("ctx: <mpv_handle>"));
+    mpv.unobserve(iter.into_token());
+    // Panics if `Seek` is still observed.
+    drop(mpv.observe_events(&[Event::Seek]).unwrap());
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn observe_property_with_initial() {
+    use crate::events::sync::{Event, PropertyData};
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("volume", 42i64).unwrap();
+    let mut iter = mpv
+        .observe_property_with_initial("volume", Format::Int64)
+        .unwrap();
+    match &iter.next().unwrap()[..] {
+        [Event::PropertyChange {
+            name,
+            data: PropertyData::Int64(42),
+        }] => assert_eq!("volume", name),
+        evs => panic!("expected the initial volume, got {:?}", evs),
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn event_iter_shutdown() {
+    use crate::events::sync::Event;
+
+    let mpv = Mpv::new().unwrap();
+    let iter = mpv.observe_events(&[Event::Idle]).unwrap();
+    mpv.quit(Some(0)).unwrap();
+    let last = (&iter).into_iter().last().unwrap();
+    assert!(matches!(last.last(), Some(Event::Shutdown)));
+    assert!(iter.iter().next().is_none());
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn event_iter_shutdown_all() {
+    use crate::events::sync::Event;
+
+    let mpv = Mpv::new().unwrap();
+    let first = mpv.observe_events(&[Event::Idle]).unwrap();
+    let second = mpv.observe_events(&[Event::Seek]).unwrap();
+    mpv.quit(Some(0)).unwrap();
+    for iter in &[first, second] {
+        let last = iter.iter().last().unwrap();
+        assert!(matches!(last.last(), Some(Event::Shutdown)));
+        assert!(iter.iter().next().is_none());
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn disabled_events() {
+    let mpv = Mpv::new().unwrap();
+    while unsafe { (*mpv_sys::mpv_wait_event(mpv.raw_handle(), 0.)).event_id }
+        != events::mpv_event_id::None
+    {}
+
+    // None of the events caused by this are enabled, so `Shutdown` is received first.
+    mpv.playlist_load_files(&[("/no/such/file", FileState::Replace, None)])
+        .unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.command("quit", &[]).unwrap();
+    loop {
+        match unsafe { (*mpv_sys::mpv_wait_event(mpv.raw_handle(), 1.)).event_id } {
+            events::mpv_event_id::None => {}
+            id => {
+                assert_eq!(events::mpv_event_id::Shutdown, id);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn for_each_ref() {
+    use crate::events::sync::{Event, EventRef, PropertyData, PropertyDataRef};
+
+    let mpv = Mpv::new().unwrap();
+    let mut iter = mpv
+        .observe_events(&[Event::PropertyChange {
+            name: "media-title".to_owned(),
+            data: PropertyData::String(String::new()),
+        }])
+        .unwrap();
+    mpv.set_property("force-media-title", "foo").unwrap();
+    mpv.command("quit", &[]).unwrap();
+
+    let mut titles = Vec::new();
+    iter.for_each_ref(|ev| {
+        if let EventRef::PropertyChange {
+            data: PropertyDataRef::String(title),
+            ..
+        } = ev
+        {
+            titles.push(title.to_owned());
+        }
+    });
+    assert_eq!(Some("foo"), titles.last().map(String::as_str));
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn coalesce_properties() {
+    use crate::events::sync::{Event, EventAdaptors, PropertyData};
+
+    let mpv = Mpv::new().unwrap();
+    let iter = mpv
+        .observe_events(&[Event::PropertyChange {
+            name: "volume".to_owned(),
+            data: PropertyData::Int64(0),
+        }])
+        .unwrap();
+    for volume in 0..=50 {
+        mpv.set_property("volume", volume).unwrap();
+    }
+    for batch in iter.coalesce_properties() {
+        assert_eq!(1, batch.len());
+        if let Event::PropertyChange {
+            data: PropertyData::Int64(50),
+            ..
+        } = batch[0]
+        {
+            break;
+        }
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn wait_for_event() {
+    use crate::events::sync::Event;
+
+    let mpv = Mpv::new().unwrap();
+    let _iter = mpv.observe_events(&[Event::Seek]).unwrap();
+    assert_eq!(
+        Err(Error::Timeout),
+        mpv.wait_for_event(Event::Seek, Duration::from_millis(10))
+            .map(|_| ())
+    );
+    if let Event::PropertyChange { name, .. } = mpv
+        .wait_for_event(Event::empty_propertychange("pause".into()), Duration::MAX)
+        .unwrap()
+    {
+        assert_eq!("pause", name);
+    } else {
+        panic!("Expected PropertyChange");
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn discard_pending() {
+    use crate::events::sync::{Event, Events};
+
+    let mpv = Mpv::new().unwrap();
+    let mut volume = mpv
+        .observe_events(
+            &Events::new()
+                .property("volume", Format::Double)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    let mut mute = mpv
+        .observe_events(
+            &Events::new()
+                .property("mute", Format::Flag)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    mpv.set_property("volume", 42.).unwrap();
+    mpv.set_property("mute", true).unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    assert!(volume.discard_pending() >= 1);
+    assert_eq!(0, volume.discard_pending());
+    // The change of `mute` is kept for its own `EventIter`.
+    assert!(mute.peek_pending() >= 1);
+    assert!(matches!(
+        &mute.next().unwrap()[..],
+        [Event::PropertyChange { .. }, ..]
+    ));
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn event_iter_size_hint() {
+    use crate::events::sync::{Event, Events};
+
+    let mpv = Mpv::new().unwrap();
+    let seek = mpv.observe_events(&[Event::Seek]).unwrap();
+    assert_eq!((0, None), seek.size_hint());
+    mpv.unobserve(seek.into_token());
+
+    let mut iter = mpv
+        .observe_events(
+            &Events::new()
+                .property("volume", Format::Double)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+    mpv.set_property("volume", 42.).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!((1, None), iter.size_hint());
+    assert!(matches!(
+        &iter.next().unwrap()[..],
+        [Event::PropertyChange { .. }, ..]
+    ));
+
+    mpv.quit(Some(0)).unwrap();
+    let last = iter.iter().last().unwrap();
+    assert!(matches!(last.last(), Some(Event::Shutdown)));
+    assert_eq!((0, Some(0)), iter.size_hint());
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn seeking_events() {
+    let mpv = Mpv::new().unwrap();
+    // `seeking` is unavailable without a file, so nothing is yielded yet.
+    drop(mpv.seeking_events().unwrap());
+    // Would panic if `seeking` was still observed.
+    drop(mpv.seeking_events().unwrap());
+
+    mpv.set_property("vo", "null").unwrap();
+    mpv.set_property("ao", "null").unwrap();
+    mpv.set_property("pause", true).unwrap();
+    mpv.load_file(
+        "av://lavfi:testsrc=size=320x240:duration=10",
+        FileState::Replace,
+        &[],
+    )
+    .unwrap();
+    mpv.wait_for_property("estimated-frame-number", 0i64, Duration::from_secs(10))
+        .unwrap();
+
+    // The initial value is yielded once, although libmpv reports it as well.
+    let mut seeking = mpv.seeking_events().unwrap();
+    assert_eq!(Some(false), seeking.next());
+    mpv.seek_absolute(5.).unwrap();
+    assert_eq!(Some(true), seeking.next());
+    assert_eq!(Some(false), seeking.next());
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn property_watcher() {
+    let mpv = Mpv::new().unwrap();
+    let mut watcher = mpv.watch_property::<i64>("volume").unwrap();
+    assert_eq!("volume", watcher.name());
+    for volume in 0..=50 {
+        mpv.set_property("volume", volume).unwrap();
+    }
+    assert_eq!(50, watcher.current_value().unwrap());
+    while watcher.changed().unwrap() != 50 {}
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn filtered_event_iter() {
+    use crate::events::sync::{Event, PropertyData};
+
+    let mpv = Mpv::new().unwrap();
+    let mut iter = mpv
+        .observe_events(&[Event::PropertyChange {
+            name: "volume".to_owned(),
+            data: PropertyData::Int64(0),
+        }])
+        .unwrap()
+        .with_filter(|ev| match *ev {
+            Event::PropertyChange {
+                data: PropertyData::Int64(v),
+                ..
+            } => v >= 50,
+            _ => true,
+        });
+    mpv.set_property("volume", 10i64).unwrap();
+    mpv.set_property("volume", 60i64).unwrap();
+    for ev in iter.next().unwrap() {
+        if let Event::PropertyChange {
+            data: PropertyData::Int64(v),
+            ..
+        } = ev
+        {
+            assert!(v >= 50);
+        }
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn owned_event_iter() {
+    use crate::events::sync::{Event, OwnedEventIter};
+
+    struct Player {
+        events: OwnedEventIter,
+    }
+
+    let player = Player {
+        events: Mpv::new().unwrap().into_events(&[Event::Idle]).unwrap(),
+    };
+    player.events.mpv().command("quit", &[]).unwrap();
+    assert!(player.events.last().is_some());
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn observe_property_format() {
+    use crate::events::sync::{Event, PropertyData};
+
+    let mpv = Mpv::new().unwrap();
+    assert!(matches!(
+        mpv.observe_events(&[Event::PropertyChange {
+            name: "volume".to_owned(),
+            data: PropertyData::Flag(false),
+        }]),
+        Err(Error::Raw(mpv_error::PropertyFormat))
+    ));
+    // The property wasn't registered, so observing it correctly doesn't panic.
+    drop(
+        mpv.observe_events(&[Event::PropertyChange {
+            name: "volume".to_owned(),
+            data: PropertyData::Double(0.),
+        }])
+        .unwrap(),
+    );
+
+    let mut iter = mpv
+        .observe_events(&[Event::PropertyChange {
+            name: "volume".to_owned(),
+            data: PropertyData::None,
+        }])
+        .unwrap();
+    mpv.set_property("volume", 42i64).unwrap();
+    assert!(matches!(
+        &iter.next().unwrap()[..],
+        [Event::PropertyChange {
+            data: PropertyData::None,
+            ..
+        }]
+    ));
+}
+
+#[cfg(all(feature = "events_sync", feature = "unknown_events"))]
+#[cfg_attr(all(feature = "events_sync", feature = "unknown_events"), test)]
+fn unknown_event() {
+    use crate::events::sync::Event;
+
+    let raw = mpv_sys::mpv_event {
+        event_id: 1000,
+        error: 0,
+        reply_userdata: 0,
+        data: std::ptr::null_mut(),
+    };
+    // The handle is only used for `VideoReconfig`.
+    match Event::from_raw(&raw, std::ptr::NonNull::dangling()) {
+        Event::Unknown { id } => assert_eq!(1000, id),
+        ev => panic!("expected Event::Unknown, got {:?}", ev),
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn events_builder() {
+    use crate::events::sync::{Event, Events, PropertyData};
+
+    let events = Events::new()
+        .property("pause", Format::Flag)
+        .property("time-pos", Format::Double)
+        .log(LogLevel::Info)
+        .file_loaded()
+        .build()
+        .unwrap();
+    assert_eq!(4, events.len());
+    assert!(matches!(
+        events[1],
+        Event::PropertyChange {
+            data: PropertyData::Double(_),
+            ..
+        }
+    ));
+
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        Events::new()
+            .property("pause", Format::Flag)
+            .property_notify("pause")
+            .build()
+            .map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        Events::new().seek().seek().build().map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::InvalidArgument),
+        Events::new()
+            .property("metadata", Format::Node)
+            .build()
+            .map(|_| ())
+    );
+
+    let mpv = Mpv::new().unwrap();
+    mpv.observe_events(&events).unwrap();
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn observe_events_rollback() {
+    use crate::events::sync::{Event, PropertyData};
+
+    let mpv = Mpv::new().unwrap();
+    assert!(mpv
+        .observe_events(&[
+            Event::Seek,
+            Event::PropertyChange {
+                name: "volume".to_owned(),
+                data: PropertyData::Double(0.),
+            },
+            Event::PropertyChange {
+                name: "a\0b".to_owned(),
+                data: PropertyData::Double(0.),
+            },
+        ])
+        .is_err());
+
+    // libmpv rejects the unknown event after the others were enabled.
+    #[cfg(feature = "unknown_events")]
+    assert_eq!(
+        Err(Error::Raw(mpv_error::InvalidParameter)),
+        mpv.observe_events(&[
+            Event::Seek,
+            Event::empty_logmessage(LogLevel::Info),
+            Event::PropertyChange {
+                name: "volume".to_owned(),
+                data: PropertyData::Double(0.),
+            },
+            Event::Unknown { id: 1000 },
+        ])
+        .map(|_| ())
+    );
+
+    // Would panic if anything was left observed.
+    mpv.observe_events(&[
+        Event::Seek,
+        Event::empty_logmessage(LogLevel::Info),
+        Event::PropertyChange {
+            name: "volume".to_owned(),
+            data: PropertyData::Double(0.),
+        },
+    ])
+    .unwrap();
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn playlist_entry_ids_sync() {
+    use crate::events::sync::{Event, Events};
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("ao", "null").unwrap();
+    let iter = mpv
+        .observe_events(&Events::new().start_file().end_file().build().unwrap())
+        .unwrap();
+    mpv.load_file("av://lavfi:anullsrc=d=0.1", FileState::Replace, &[])
+        .unwrap();
+    let expected = playlist_entry_id(&mpv, 0);
+
+    let mut ids = Vec::new();
+    'events: for batch in &iter {
+        for ev in batch {
+            match ev {
+                Event::StartFile { playlist_entry_id } => ids.push(playlist_entry_id),
+                Event::EndFile {
+                    playlist_entry_id, ..
+                } => {
+                    ids.push(playlist_entry_id);
+                    break 'events;
+                }
+                _ => {}
+            }
+        }
+    }
+    assert_eq!(vec![expected, expected], ids);
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn queue_overflow() {
+    use crate::events::sync::{Event, Events};
+
+    let mpv = Mpv::new().unwrap();
+    let mut iter = mpv
+        .observe_events(&Events::new().command_reply().start_file().build().unwrap())
+        .unwrap();
+
+    // Replies reserve room in the queue, so this fills it up without racing the core.
+    let mut id = 0;
+    loop {
+        match mpv.command_node_async(id, "expand-text", &[MpvNode::String(String::new())]) {
+            Ok(()) => id += 1,
+            Err(Error::Raw(mpv_error::EventQueueFull)) => break,
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+    // `StartFile` doesn't fit anymore.
+    mpv.load_file("av://lavfi:anullsrc", FileState::Replace, &[])
+        .unwrap();
+
+    let mut overflowed = false;
+    for _ in 0..=id {
+        if iter
+            .next()
+            .unwrap()
+            .iter()
+            .any(|ev| matches!(ev, Event::QueueOverflow))
+        {
+            overflowed = true;
+            break;
+        }
+    }
+    assert!(overflowed);
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn command_node_async() {
+    use crate::events::sync::Event;
+
+    let mpv = Mpv::new().unwrap();
+    let mut iter = mpv.observe_events(&[Event::empty_command_reply()]).unwrap();
+
+    // Unknown commands are rejected before they are queued.
+    assert!(mpv.command_node_async(1, "not-a-command", &[]).is_err());
+    mpv.command_node_async(2, "expand-text", &[MpvNode::String("text".to_owned())])
+        .unwrap();
+
+    loop {
+        let reply = iter
+            .next()
+            .unwrap()
+            .into_iter()
+            .find_map(|event| match event {
+                Event::CommandReply {
+                    reply_userdata,
+                    result,
+                } => Some((reply_userdata, result)),
+                _ => None,
+            });
+        if let Some(reply) = reply {
+            assert_eq!((2, Ok(MpvNode::String("text".to_owned()))), reply);
+            break;
+        }
+    }
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn event_driver() {
+    use crate::events::sync::Event;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Driver(Arc<AtomicUsize>);
+    impl EventDriver for Driver {
+        fn wake(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mpv = Mpv::new().unwrap();
+    let wakes = Arc::new(AtomicUsize::new(0));
+    mpv.set_event_driver(Driver(wakes.clone()));
+    let mut iter = mpv
+        .observe_events(&[Event::empty_propertychange("volume".to_owned())])
+        .unwrap();
+
+    // The `EventIter` is still woken up by the default driver.
+    assert!(!iter.next().unwrap().is_empty());
+    assert!(wakes.load(Ordering::SeqCst) > 0);
+}
+
+#[cfg(feature = "events_sync")]
+#[cfg_attr(feature = "events_sync", test)]
+fn property_type() {
+    use crate::events::sync::{PropertyData, PropertyType};
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("volume", 42i64).unwrap();
+    let data = mpv
+        .get_property::<i64>("volume")
+        .unwrap()
+        .into_property_data();
+    assert_eq!(42, i64::from_property_data(data).unwrap());
+
+    assert_eq!(
+        Err(Error::Raw(mpv_error::PropertyFormat)),
+        bool::from_property_data(PropertyData::Double(1.))
+    );
+    assert_eq!(
+        "foo",
+        String::from_property_data(PropertyData::OsdString("foo".to_owned())).unwrap()
+    );
+}
+
 // Used to approximate correctness of non-deterministic event order
 macro_rules! assert_eq_any {
     ($left:expr, $( $right:expr ),+) => (
@@ -69,6 +1744,43 @@ macro_rules! assert_eq_any {
     )
 }
 
+// The id libmpv reports for the playlist entry at `index`, which is 0 before client API 1.108.
+fn playlist_entry_id(mpv: &Mpv, index: usize) -> i64 {
+    if unsafe { mpv_sys::mpv_client_api_version() } >= (1 << 16 | 108) {
+        mpv.get_property(&format!("playlist/{}/id", index)).unwrap()
+    } else {
+        0
+    }
+}
+
+#[cfg(feature = "events_simple")]
+#[cfg_attr(feature = "events_simple", test)]
+fn playlist_entry_ids_simple() {
+    use crate::events::simple::Event;
+
+    let mpv = Mpv::new().unwrap();
+    mpv.set_property("ao", "null").unwrap();
+    mpv.load_file("av://lavfi:anullsrc=d=0.1", FileState::Replace, &[])
+        .unwrap();
+    let expected = playlist_entry_id(&mpv, 0);
+
+    let mut ids = Vec::new();
+    loop {
+        match unsafe { mpv.wait_event(10.) } {
+            Some(Ok(Event::StartFile { playlist_entry_id })) => ids.push(playlist_entry_id),
+            Some(Ok(Event::EndFile {
+                playlist_entry_id, ..
+            })) => {
+                ids.push(playlist_entry_id);
+                break;
+            }
+            None => panic!("timed out waiting for EndFile"),
+            _ => {}
+        }
+    }
+    assert_eq!(vec![expected, expected], ids);
+}
+
 #[cfg(feature = "events_simple")]
 #[cfg_attr(feature = "events_simple", test)]
 fn events_simple() {
@@ -112,10 +1824,10 @@ fn events_simple() {
         None,
     )])
     .unwrap();
-    assert_eq!(
-        Event::StartFile,
-        unsafe { mpv.wait_event(10.) }.unwrap().unwrap()
-    );
+    assert!(matches!(
+        unsafe { mpv.wait_event(10.) }.unwrap().unwrap(),
+        Event::StartFile { .. }
+    ));
     assert_eq!(
         Event::PropertyChange {
             name: "media-title",
@@ -138,19 +1850,28 @@ fn events_simple() {
         None,
     )])
     .unwrap();
-    assert_eq!(
-        Event::StartFile,
-        unsafe { mpv.wait_event(10.) }.unwrap().unwrap()
-    );
+    assert!(matches!(
+        unsafe { mpv.wait_event(10.) }.unwrap().unwrap(),
+        Event::StartFile { .. }
+    ));
     // The order of events is unfortunately non-deterministic.
     for _ in 0..7 {
         // A possible order is:
         //      StartFile -> AudioReconfig -> FileLoaded -> AudioReconfig -> PropertyChange
+        let ev = unsafe { mpv.wait_event(10.) }.unwrap().unwrap();
+        if let Event::VideoReconfig { params } = ev {
+            if let Some(params) = params {
+                assert!(params.width < 240);
+            }
+            continue;
+        }
+        if let Event::FileLoaded { tracks, .. } = ev {
+            assert!(tracks.iter().any(|track| track.kind == "audio"));
+            continue;
+        }
         assert_eq_any!(
-            unsafe { mpv.wait_event(10.) }.unwrap().unwrap(),
+            ev,
             Event::AudioReconfig,
-            Event::VideoReconfig,
-            Event::FileLoaded,
             // Either both, or only the second title Event will trigger because of coalescence
             Event::PropertyChange {
                 name: "media-title",
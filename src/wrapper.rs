@@ -32,12 +32,25 @@ mod errors {
             index: usize,
             error: Rc<Error>,
         },
+        SetProperty {
+            name: String,
+            error: Rc<Error>,
+        },
+        Batch {
+            index: usize,
+            error: Rc<Error>,
+        },
         VersionMismatch {
             linked: ctype::c_ulong,
             loaded: ctype::c_ulong,
         },
+        InvalidArgument,
+        InvalidGeometry(String),
         InvalidUtf8,
+        Io(std::io::ErrorKind),
         Null,
+        CommandFailed,
+        Timeout,
         Raw(crate::MpvError),
     }
 
@@ -51,6 +64,11 @@ mod errors {
             Error::InvalidUtf8
         }
     }
+    impl From<std::io::Error> for Error {
+        fn from(other: std::io::Error) -> Error {
+            Error::Io(other.kind())
+        }
+    }
     impl From<crate::MpvError> for Error {
         fn from(other: crate::MpvError) -> Error {
             Error::Raw(other)
@@ -92,19 +110,36 @@ pub mod protocol;
 #[cfg(feature = "render")]
 /// Custom rendering
 pub mod render;
+/// Time formatting and parsing
+pub mod util;
 
 use super::*;
 
+#[cfg(feature = "events_simple")]
+use events::simple::PropertyData;
+#[cfg(feature = "events_sync")]
+use events::sync::PropertyData;
 #[cfg(feature = "events_sync")]
 use parking_lot::{self, Mutex};
 
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Read;
 use std::mem::MaybeUninit;
 use std::ops::Deref;
 use std::os::raw as ctype;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::ptr::{self, NonNull};
-#[cfg(feature = "protocols")]
+use std::slice;
+use std::str::FromStr;
+#[cfg(any(feature = "protocols", feature = "events_sync"))]
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 fn mpv_err<T>(ret: T, err: ctype::c_int) -> Result<T> {
     if err == 0 {
@@ -114,6 +149,82 @@ fn mpv_err<T>(ret: T, err: ctype::c_int) -> Result<T> {
     }
 }
 
+// Quote an argument passed to `Mpv::command`, so that it's parsed as a single argument.
+fn quote_arg(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Join options into mpv's `key1=value1,key2=value2` syntax. Values that contain characters with
+// a special meaning are quoted as `%length%value`.
+pub(crate) fn key_value_list(options: &[(&str, &str)]) -> Result<String> {
+    let mut list = String::new();
+    for (i, &(key, value)) in options.iter().enumerate() {
+        if key.is_empty() || key.contains([',', '=']) {
+            return Err(Error::InvalidArgument);
+        }
+        if i > 0 {
+            list.push(',');
+        }
+        list.push_str(key);
+        list.push('=');
+        if value.contains(|c| ",%\"'[]".contains(c)) {
+            list.push_str(&format!("%{}%", value.len()));
+        }
+        list.push_str(value);
+    }
+    Ok(list)
+}
+
+// The timeout for `mpv_wait_event` until `deadline`, where `None` waits without one, e.g. because
+// the `Duration` was too large to add. Returns `Error::Timeout` once the deadline has passed.
+fn wait_timeout(deadline: Option<Instant>) -> Result<f64> {
+    match deadline {
+        Some(deadline) => {
+            let now = Instant::now();
+            if now >= deadline {
+                Err(Error::Timeout)
+            } else {
+                Ok((deadline - now).as_secs_f64())
+            }
+        }
+        None => Ok(-1.),
+    }
+}
+
+// Used to give the input sections of `Mpv::bind_key` unique names.
+static NEXT_SECTION_ID: AtomicU64 = AtomicU64::new(0);
+
+// Reply ids of the requests issued by the blocking helpers, unique across all instances.
+static NEXT_REPLY_ID: AtomicU64 = AtomicU64::new(0);
+
+// A separate client of an `Mpv` core, so that waiting for replies doesn't consume the events of
+// the user's handle.
+struct SubClient(NonNull<mpv_sys::mpv_handle>);
+
+impl SubClient {
+    fn new(mpv: &Mpv) -> Result<SubClient> {
+        let ctx = unsafe { mpv_sys::mpv_create_client(mpv.ctx.as_ptr(), ptr::null()) };
+        NonNull::new(ctx).map(SubClient).ok_or(Error::Null)
+    }
+}
+
+impl Drop for SubClient {
+    fn drop(&mut self) {
+        unsafe { mpv_sys::mpv_destroy(self.0.as_ptr()) };
+    }
+}
+
 #[allow(missing_docs)]
 /// This trait describes which types are allowed to be passed to getter mpv APIs.
 pub unsafe trait GetData: Sized {
@@ -170,9 +281,10 @@ unsafe impl SetData for i64 {
 unsafe impl GetData for bool {
     #[inline]
     fn get_from_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(mut fun: F) -> Result<bool> {
-        let mut val = MaybeUninit::uninit();
+        // mpv writes flags as an `int`.
+        let mut val = MaybeUninit::<ctype::c_int>::uninit();
         let _ = fun(val.as_mut_ptr() as *mut _)?;
-        Ok(unsafe { val.assume_init() })
+        Ok(unsafe { val.assume_init() } != 0)
     }
 
     #[inline]
@@ -184,8 +296,8 @@ unsafe impl GetData for bool {
 unsafe impl SetData for bool {
     #[inline]
     fn call_as_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(self, mut fun: F) -> Result<T> {
-        let mut cpy: i64 = if self { 1 } else { 0 };
-        fun(&mut cpy as *mut i64 as *mut _)
+        let mut cpy: ctype::c_int = if self { 1 } else { 0 };
+        fun(&mut cpy as *mut ctype::c_int as *mut _)
     }
 
     #[inline]
@@ -269,6 +381,416 @@ unsafe impl<'a> SetData for &'a str {
     }
 }
 
+// The data that the `mpv_node`s created by `MpvNode::to_raw` point to. Moving this doesn't move
+// the data itself, which is also why the lists and byte arrays are boxed.
+#[derive(Default)]
+#[allow(clippy::vec_box)]
+struct RawNodeStorage {
+    strings: Vec<CString>,
+    lists: Vec<Box<mpv_sys::mpv_node_list>>,
+    values: Vec<Vec<mpv_sys::mpv_node>>,
+    keys: Vec<Vec<*mut ctype::c_char>>,
+    byte_arrays: Vec<Box<mpv_sys::mpv_byte_array>>,
+}
+
+impl RawNodeStorage {
+    fn push_list(
+        &mut self,
+        values: &mut Vec<mpv_sys::mpv_node>,
+        keys: *mut *mut ctype::c_char,
+    ) -> *mut mpv_sys::mpv_node_list {
+        let mut list = Box::new(mpv_sys::mpv_node_list {
+            num: values.len() as _,
+            values: values.as_mut_ptr(),
+            keys,
+        });
+        let list_ptr = &mut *list as *mut _;
+        self.lists.push(list);
+        list_ptr
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An owned copy of a `mpv_node`, the structured data type used by properties such as lists.
+pub enum MpvNode {
+    #[allow(missing_docs)]
+    String(String),
+    #[allow(missing_docs)]
+    Flag(bool),
+    #[allow(missing_docs)]
+    Int64(i64),
+    #[allow(missing_docs)]
+    Double(f64),
+    #[allow(missing_docs)]
+    Array(Vec<MpvNode>),
+    #[allow(missing_docs)]
+    Map(HashMap<String, MpvNode>),
+    #[allow(missing_docs)]
+    ByteArray(Vec<u8>),
+    /// The node holds no value.
+    None,
+}
+
+impl MpvNode {
+    unsafe fn from_raw(node: &mpv_sys::mpv_node) -> Result<MpvNode> {
+        Ok(match node.format {
+            mpv_format::String => MpvNode::String(mpv_cstr_to_str!(node.u.string)?.to_owned()),
+            mpv_format::Flag => MpvNode::Flag(node.u.flag != 0),
+            mpv_format::Int64 => MpvNode::Int64(node.u.int64),
+            mpv_format::Double => MpvNode::Double(node.u.double_),
+            mpv_format::NodeArray => {
+                let list = &*node.u.list;
+                let mut array = Vec::with_capacity(list.num as usize);
+                for i in 0..list.num as isize {
+                    array.push(MpvNode::from_raw(&*list.values.offset(i))?);
+                }
+                MpvNode::Array(array)
+            }
+            mpv_format::NodeMap => {
+                let list = &*node.u.list;
+                let mut map = HashMap::with_capacity(list.num as usize);
+                for i in 0..list.num as isize {
+                    map.insert(
+                        mpv_cstr_to_str!(*list.keys.offset(i))?.to_owned(),
+                        MpvNode::from_raw(&*list.values.offset(i))?,
+                    );
+                }
+                MpvNode::Map(map)
+            }
+            mpv_format::ByteArray => {
+                let ba = &*node.u.ba;
+                if ba.size == 0 {
+                    MpvNode::ByteArray(Vec::new())
+                } else {
+                    MpvNode::ByteArray(
+                        slice::from_raw_parts(ba.data as *const u8, ba.size).to_vec(),
+                    )
+                }
+            }
+            mpv_format::None => MpvNode::None,
+            _ => return Err(Error::Raw(mpv_error::UnknownFormat)),
+        })
+    }
+
+    // Convert to a `mpv_node` that points into `storage`, so it's valid as long as that is.
+    fn to_raw(&self, storage: &mut RawNodeStorage) -> Result<mpv_sys::mpv_node> {
+        let (u, format) = match *self {
+            MpvNode::String(ref v) => {
+                let v = CString::new(&v[..])?;
+                let string = v.as_ptr() as *mut _;
+                storage.strings.push(v);
+                (
+                    mpv_sys::mpv_node__bindgen_ty_1 { string },
+                    mpv_format::String,
+                )
+            }
+            MpvNode::Flag(v) => (
+                mpv_sys::mpv_node__bindgen_ty_1 {
+                    flag: if v { 1 } else { 0 },
+                },
+                mpv_format::Flag,
+            ),
+            MpvNode::Int64(int64) => (mpv_sys::mpv_node__bindgen_ty_1 { int64 }, mpv_format::Int64),
+            MpvNode::Double(double_) => (
+                mpv_sys::mpv_node__bindgen_ty_1 { double_ },
+                mpv_format::Double,
+            ),
+            MpvNode::Array(ref array) => {
+                let mut values = array
+                    .iter()
+                    .map(|v| v.to_raw(storage))
+                    .collect::<Result<Vec<_>>>()?;
+                let list = storage.push_list(&mut values, ptr::null_mut());
+                storage.values.push(values);
+                (
+                    mpv_sys::mpv_node__bindgen_ty_1 { list },
+                    mpv_format::NodeArray,
+                )
+            }
+            MpvNode::Map(ref map) => {
+                let mut keys = Vec::with_capacity(map.len());
+                let mut values = Vec::with_capacity(map.len());
+                for (k, v) in map {
+                    let k = CString::new(&k[..])?;
+                    keys.push(k.as_ptr() as *mut _);
+                    storage.strings.push(k);
+                    values.push(v.to_raw(storage)?);
+                }
+                let list = storage.push_list(&mut values, keys.as_mut_ptr());
+                storage.values.push(values);
+                storage.keys.push(keys);
+                (
+                    mpv_sys::mpv_node__bindgen_ty_1 { list },
+                    mpv_format::NodeMap,
+                )
+            }
+            MpvNode::ByteArray(ref v) => {
+                let mut ba = Box::new(mpv_sys::mpv_byte_array {
+                    data: v.as_ptr() as *mut _,
+                    size: v.len(),
+                });
+                let ba_ptr = &mut *ba as *mut _;
+                storage.byte_arrays.push(ba);
+                (
+                    mpv_sys::mpv_node__bindgen_ty_1 { ba: ba_ptr },
+                    mpv_format::ByteArray,
+                )
+            }
+            MpvNode::None => (
+                mpv_sys::mpv_node__bindgen_ty_1 { flag: 0 },
+                mpv_format::None,
+            ),
+        };
+        Ok(mpv_sys::mpv_node { u, format })
+    }
+
+    #[inline]
+    /// The string value, if this is a `String` node.
+    pub fn as_str(&self) -> Option<&str> {
+        if let MpvNode::String(ref v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    /// The flag value, if this is a `Flag` node.
+    pub fn as_bool(&self) -> Option<bool> {
+        if let MpvNode::Flag(v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    /// The integer value, if this is an `Int64` node.
+    pub fn as_i64(&self) -> Option<i64> {
+        if let MpvNode::Int64(v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    /// The floating point value, if this is a `Double` or `Int64` node.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            MpvNode::Double(v) => Some(v),
+            MpvNode::Int64(v) => Some(v as f64),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    /// The elements, if this is an `Array` node.
+    pub fn as_array(&self) -> Option<&[MpvNode]> {
+        if let MpvNode::Array(ref v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    /// The entries, if this is a `Map` node.
+    pub fn as_map(&self) -> Option<&HashMap<String, MpvNode>> {
+        if let MpvNode::Map(ref v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
+/// Callbacks for walking an `MpvNode`, or a node of libmpv without copying it, see
+/// `Mpv::get_property_visit` and `Mpv::command_visit`.
+///
+/// All callbacks do nothing by default.
+pub trait NodeVisitor {
+    #[allow(missing_docs)]
+    fn visit_str(&mut self, _v: &str) {}
+    #[allow(missing_docs)]
+    fn visit_bool(&mut self, _v: bool) {}
+    #[allow(missing_docs)]
+    fn visit_i64(&mut self, _v: i64) {}
+    #[allow(missing_docs)]
+    fn visit_f64(&mut self, _v: f64) {}
+    #[allow(missing_docs)]
+    fn visit_bytes(&mut self, _v: &[u8]) {}
+    #[allow(missing_docs)]
+    fn visit_none(&mut self) {}
+    /// Called before the `len` elements of an array are visited.
+    fn enter_array(&mut self, _len: usize) {}
+    /// Called after all elements of an array were visited.
+    fn leave_array(&mut self) {}
+    /// Called before the `len` entries of a map are visited.
+    fn enter_map(&mut self, _len: usize) {}
+    /// Called before the value of the map entry `key` is visited, which is skipped if this
+    /// returns false.
+    fn map_key(&mut self, _key: &str) -> bool {
+        true
+    }
+    /// Called after all entries of a map were visited.
+    fn leave_map(&mut self) {}
+}
+
+impl MpvNode {
+    #[inline]
+    /// Walk this node with `visitor`. Map entries are visited in arbitrary order.
+    pub fn visit<V: NodeVisitor>(&self, visitor: &mut V) {
+        match *self {
+            MpvNode::String(ref v) => visitor.visit_str(v),
+            MpvNode::Flag(v) => visitor.visit_bool(v),
+            MpvNode::Int64(v) => visitor.visit_i64(v),
+            MpvNode::Double(v) => visitor.visit_f64(v),
+            MpvNode::Array(ref v) => {
+                visitor.enter_array(v.len());
+                for node in v {
+                    node.visit(visitor);
+                }
+                visitor.leave_array();
+            }
+            MpvNode::Map(ref v) => {
+                visitor.enter_map(v.len());
+                for (key, node) in v {
+                    if visitor.map_key(key) {
+                        node.visit(visitor);
+                    }
+                }
+                visitor.leave_map();
+            }
+            MpvNode::ByteArray(ref v) => visitor.visit_bytes(v),
+            MpvNode::None => visitor.visit_none(),
+        }
+    }
+
+    // Like `visit`, but for a node of libmpv, which is not copied.
+    unsafe fn visit_raw<V: NodeVisitor>(node: &mpv_sys::mpv_node, visitor: &mut V) -> Result<()> {
+        match node.format {
+            mpv_format::String => visitor.visit_str(mpv_cstr_to_str!(node.u.string)?),
+            mpv_format::Flag => visitor.visit_bool(node.u.flag != 0),
+            mpv_format::Int64 => visitor.visit_i64(node.u.int64),
+            mpv_format::Double => visitor.visit_f64(node.u.double_),
+            mpv_format::NodeArray => {
+                let list = &*node.u.list;
+                visitor.enter_array(list.num as usize);
+                for i in 0..list.num as isize {
+                    MpvNode::visit_raw(&*list.values.offset(i), visitor)?;
+                }
+                visitor.leave_array();
+            }
+            mpv_format::NodeMap => {
+                let list = &*node.u.list;
+                visitor.enter_map(list.num as usize);
+                for i in 0..list.num as isize {
+                    if visitor.map_key(mpv_cstr_to_str!(*list.keys.offset(i))?) {
+                        MpvNode::visit_raw(&*list.values.offset(i), visitor)?;
+                    }
+                }
+                visitor.leave_map();
+            }
+            mpv_format::ByteArray => {
+                let ba = &*node.u.ba;
+                if ba.size == 0 {
+                    visitor.visit_bytes(&[]);
+                } else {
+                    visitor.visit_bytes(slice::from_raw_parts(ba.data as *const u8, ba.size));
+                }
+            }
+            mpv_format::None => visitor.visit_none(),
+            _ => return Err(Error::Raw(mpv_error::UnknownFormat)),
+        }
+        Ok(())
+    }
+}
+
+// Collects the strings of a node array, see `Mpv::property_names`.
+struct StringList(Vec<String>);
+
+impl NodeVisitor for StringList {
+    fn visit_str(&mut self, v: &str) {
+        self.0.push(v.to_owned());
+    }
+
+    fn enter_array(&mut self, len: usize) {
+        self.0.reserve(len);
+    }
+}
+
+#[cfg(feature = "serde_json")]
+/// `ByteArray` nodes are converted to arrays of numbers, and non-finite `Double`s to `null`.
+impl From<MpvNode> for serde_json::Value {
+    fn from(node: MpvNode) -> serde_json::Value {
+        use serde_json::Value;
+
+        match node {
+            MpvNode::String(v) => Value::String(v),
+            MpvNode::Flag(v) => Value::Bool(v),
+            MpvNode::Int64(v) => Value::from(v),
+            MpvNode::Double(v) => Value::from(v),
+            MpvNode::Array(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+            MpvNode::Map(v) => Value::Object(v.into_iter().map(|(k, v)| (k, v.into())).collect()),
+            MpvNode::ByteArray(v) => Value::Array(v.into_iter().map(Value::from).collect()),
+            MpvNode::None => Value::Null,
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+/// Integers that don't fit into an `i64` are converted to `Double`s.
+///
+/// Returns `Error::InvalidArgument` for numbers that can't be represented by either.
+impl TryFrom<serde_json::Value> for MpvNode {
+    type Error = Error;
+
+    fn try_from(value: serde_json::Value) -> Result<MpvNode> {
+        use serde_json::Value;
+
+        Ok(match value {
+            Value::Null => MpvNode::None,
+            Value::Bool(v) => MpvNode::Flag(v),
+            Value::Number(v) => match v.as_i64() {
+                Some(v) => MpvNode::Int64(v),
+                None => MpvNode::Double(v.as_f64().ok_or(Error::InvalidArgument)?),
+            },
+            Value::String(v) => MpvNode::String(v),
+            Value::Array(v) => MpvNode::Array(
+                v.into_iter()
+                    .map(MpvNode::try_from)
+                    .collect::<Result<_>>()?,
+            ),
+            Value::Object(v) => MpvNode::Map(
+                v.into_iter()
+                    .map(|(k, v)| Ok((k, MpvNode::try_from(v)?)))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+}
+
+unsafe impl GetData for MpvNode {
+    #[inline]
+    fn get_from_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(
+        mut fun: F,
+    ) -> Result<MpvNode> {
+        let mut node = MaybeUninit::<mpv_sys::mpv_node>::uninit();
+        let _ = fun(node.as_mut_ptr() as *mut _)?;
+
+        let mut node = unsafe { node.assume_init() };
+        let ret = unsafe { MpvNode::from_raw(&node) };
+        unsafe { mpv_sys::mpv_free_node_contents(&mut node) };
+        ret
+    }
+
+    #[inline]
+    fn get_format() -> Format {
+        Format::Node
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 /// Subset of `mpv_format` used by the public API.
@@ -277,15 +799,35 @@ pub enum Format {
     Flag,
     Int64,
     Double,
+    Node,
 }
 
 impl Format {
     fn as_mpv_format(&self) -> MpvFormat {
         match *self {
-            Format::String => mpv_format::String,
-            Format::Flag => mpv_format::Flag,
-            Format::Int64 => mpv_format::Int64,
-            Format::Double => mpv_format::Double,
+            Format::String => MpvFormat::String,
+            Format::Flag => MpvFormat::Flag,
+            Format::Int64 => MpvFormat::Int64,
+            Format::Double => MpvFormat::Double,
+            Format::Node => MpvFormat::Node,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The direction in which `Mpv::cycle_property` cycles.
+pub enum CycleDirection {
+    #[allow(missing_docs)]
+    Up,
+    #[allow(missing_docs)]
+    Down,
+}
+
+impl CycleDirection {
+    fn val(&self) -> &str {
+        match *self {
+            CycleDirection::Up => "up",
+            CycleDirection::Down => "down",
         }
     }
 }
@@ -311,114 +853,1210 @@ impl FileState {
     }
 }
 
-pub struct Mpv {
-    /// The handle to the mpv core
-    pub ctx: NonNull<mpv_sys::mpv_handle>,
-    #[cfg(feature = "events_sync")]
-    ev_iter_notification: Box<(Mutex<bool>, parking_lot::Condvar)>,
-    #[cfg(feature = "events_sync")]
-    ev_to_observe: Mutex<Vec<events::sync::Event>>,
-    #[cfg(feature = "events_sync")]
-    ev_to_observe_properties: Mutex<::std::collections::HashMap<String, u64>>,
-    #[cfg(feature = "events_sync")]
-    ev_observed: Mutex<Vec<events::sync::Event>>,
-    #[cfg(feature = "protocols")]
-    protocols_guard: AtomicBool,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// How a subtitle is added by `Mpv::add_subtitle`.
+pub enum SubtitleFlag {
+    /// Select the subtitle immediately.
+    Select,
+    /// Don't select the subtitle, or let the default stream selection decide.
+    Auto,
+    /// Select the subtitle, reusing an already added one with the same file name. The title and
+    /// language are ignored in that case.
+    Cached,
 }
 
-unsafe impl Send for Mpv {}
-unsafe impl Sync for Mpv {}
-
-impl Drop for Mpv {
-    #[inline]
-    fn drop(&mut self) {
-        unsafe {
-            mpv_sys::mpv_terminate_destroy(self.ctx.as_ptr());
+impl SubtitleFlag {
+    fn val(&self) -> &str {
+        match *self {
+            SubtitleFlag::Select => "select",
+            SubtitleFlag::Auto => "auto",
+            SubtitleFlag::Cached => "cached",
         }
     }
 }
 
-impl Mpv {
-    #[cfg(not(feature = "events_sync"))]
+/// The decoded audio of an `Mpv` instance, see `Mpv::enable_audio_capture`.
+pub struct AudioCapture {
+    path: PathBuf,
+    file: File,
+    // Bytes of an incomplete sample, that was only partially written yet.
+    partial: Vec<u8>,
+}
+
+impl AudioCapture {
     #[inline]
-    /// Create a new `Mpv`.
-    /// The default settings can be probed by running: `$ mpv --show-profile=libmpv`
-    pub fn new() -> Result<Mpv> {
-        let api_version = unsafe { mpv_sys::mpv_client_api_version() };
-        if crate::MPV_CLIENT_API_VERSION != api_version {
-            return Err(Error::VersionMismatch {
-                linked: crate::MPV_CLIENT_API_VERSION,
-                loaded: api_version,
-            });
+    /// Read the samples that were captured since the last call, returning how many were read.
+    ///
+    /// The samples are interleaved native-endian 32-bit floats, in the layout of the
+    /// `audio-params` property.
+    /// This doesn't block, so `0` will be returned if no new samples are available.
+    pub fn read_samples(&mut self, buf: &mut [f32]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
         }
 
-        let ctx = unsafe { mpv_sys::mpv_create() };
-        if ctx.is_null() {
-            return Err(Error::Null);
+        let mut bytes = vec![0u8; buf.len() * 4 - self.partial.len()];
+        let read = self.file.read(&mut bytes)?;
+
+        self.partial.extend_from_slice(&bytes[..read]);
+        let samples = self.partial.len() / 4;
+        for (sample, raw) in buf.iter_mut().zip(self.partial.chunks_exact(4)) {
+            *sample = f32::from_ne_bytes([raw[0], raw[1], raw[2], raw[3]]);
         }
-        mpv_err((), unsafe { mpv_sys::mpv_initialize(ctx) }).or_else(|err| {
-            unsafe { mpv_sys::mpv_terminate_destroy(ctx) };
-            Err(err)
-        })?;
+        self.partial.drain(..samples * 4);
+        Ok(samples)
+    }
+}
 
-        Ok(Mpv {
-            ctx: unsafe { NonNull::new_unchecked(ctx) },
-            #[cfg(feature = "protocols")]
-            protocols_guard: AtomicBool::new(false),
-        })
+impl Drop for AudioCapture {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
     }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// An audio output device, as listed by `Mpv::audio_devices`.
+pub struct AudioDevice {
+    /// The name to pass to `Mpv::set_audio_device`, e.g. `auto` or `alsa/default`.
+    pub name: String,
+    /// A human readable description of the device, which may be empty.
+    pub description: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A snapshot of the playback state, as returned by `Mpv::playback_state`.
+///
+/// Properties that are unavailable, e.g. because no file is loaded, are `None`.
+pub struct PlaybackState {
+    #[allow(missing_docs)]
+    pub pause: bool,
+    /// Position in the current file in seconds.
+    pub time_pos: Option<f64>,
+    /// Duration of the current file in seconds.
+    pub duration: Option<f64>,
+    #[allow(missing_docs)]
+    pub volume: f64,
+    #[allow(missing_docs)]
+    pub mute: bool,
+    #[allow(missing_docs)]
+    pub media_title: Option<String>,
+    /// Whether playback reached the end of the file, and is paused because of `--keep-open`.
+    pub eof_reached: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The state of the demuxer cache, as returned by `Mpv::cache_state`.
+///
+/// Without a cache, e.g. for most local files, the fields are `None` and `false`.
+pub struct CacheState {
+    /// How full the cache is, until playback resumes after buffering, from 0 to 100.
+    pub buffering_percent: Option<i64>,
+    /// Whether playback is paused to fill the cache.
+    pub paused_for_cache: bool,
+    /// The duration of the cached data in seconds.
+    pub cache_duration: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Media tags, as returned by e.g. `Mpv::metadata`, with accessors for common tags.
+///
+/// The accessors ignore the case of the tag names, which depends on the file format.
+pub struct Metadata(pub HashMap<String, String>);
 
+impl Metadata {
     #[inline]
-    /// Load a configuration file. The path has to be absolute, and a file.
-    pub fn load_config(&self, path: &str) -> Result<()> {
-        let file = CString::new(path)?.into_raw();
-        let ret = mpv_err((), unsafe {
-            mpv_sys::mpv_load_config_file(self.ctx.as_ptr(), file)
-        });
-        unsafe { CString::from_raw(file) };
-        ret
+    /// The value of the tag `name`, ignoring its case.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| &v[..])
     }
 
     #[inline]
-    /// Send a command to the `Mpv` instance. This uses `mpv_command_string` internally,
-    /// so that the syntax is the same as described in the [manual for the input.conf]
-    /// (https://mpv.io/manual/master/#list-of-input-commands).
-    ///
-    /// Note that you may have to escape strings with `""` when they contain spaces.
-    pub fn command(&self, name: &str, args: &[&str]) -> Result<()> {
-        let mut cmd =
-            String::with_capacity(name.len() + args.iter().fold(0, |acc, e| acc + e.len() + 1));
-        cmd.push_str(name);
-
-        for elem in args {
-            cmd.push_str(" ");
-            cmd.push_str(elem);
-        }
-        let raw = CString::new(cmd)?;
-
-        mpv_err((), unsafe {
-            mpv_sys::mpv_command_string(self.ctx.as_ptr(), raw.as_ptr())
-        })
+    #[allow(missing_docs)]
+    pub fn title(&self) -> Option<&str> {
+        self.get("title")
     }
 
     #[inline]
-    /// Set the value of a property.
-    pub fn set_property<T: SetData>(&self, name: &str, data: T) -> Result<()> {
-        let name = CString::new(name)?;
-        let format = T::get_format().as_mpv_format() as _;
-        data.call_as_c_void(|ptr| {
-            mpv_err((), unsafe {
-                mpv_sys::mpv_set_property(self.ctx.as_ptr(), name.as_ptr(), format, ptr)
-            })
-        })
+    #[allow(missing_docs)]
+    pub fn artist(&self) -> Option<&str> {
+        self.get("artist")
     }
 
     #[inline]
-    /// Get the value of a property.
+    #[allow(missing_docs)]
+    pub fn album(&self) -> Option<&str> {
+        self.get("album")
+    }
+
+    #[inline]
+    /// The track number, which may be followed by the total, e.g. `3/12`.
+    pub fn track(&self) -> Option<&str> {
+        self.get("track")
+    }
+
+    #[inline]
+    #[allow(missing_docs)]
+    pub fn date(&self) -> Option<&str> {
+        self.get("date")
+    }
+
+    #[inline]
+    #[allow(missing_docs)]
+    pub fn comment(&self) -> Option<&str> {
+        self.get("comment")
+    }
+}
+
+impl From<HashMap<String, String>> for Metadata {
+    fn from(tags: HashMap<String, String>) -> Metadata {
+        Metadata(tags)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Details of the demuxer cache, as returned by `Mpv::cache_status`.
+pub struct CacheStatus {
+    /// The speed at which the cache is filled.
+    pub speed_bytes_per_sec: f64,
+    /// How full the cache is, until playback resumes after buffering, from 0 to 100.
+    pub buffering_percent: f64,
+    /// The duration of the cached data ahead of the playback position.
+    pub forward_secs: f64,
+    /// The duration of the cached data behind the playback position, that can be seeked back to.
+    pub backward_secs: f64,
+    /// Whether the demuxer ran out of data, and is waiting for more.
+    pub underrun: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The parameters of the decoded video, as returned by `Mpv::video_params` and carried by
+/// `Event::VideoReconfig`.
+///
+/// Fields that the video doesn't report are `0`, or empty.
+pub struct VideoParams {
+    /// Width of the decoded video.
+    pub width: i64,
+    /// Height of the decoded video.
+    pub height: i64,
+    /// Width of the video after aspect ratio correction.
+    pub dwidth: i64,
+    /// Height of the video after aspect ratio correction.
+    pub dheight: i64,
+    /// Display aspect ratio of the video.
+    pub aspect: f64,
+    /// Clockwise rotation of the video in degrees.
+    pub rotate: i64,
+    /// The pixel format of the decoded video, e.g. `yuv420p`.
+    pub pixelformat: String,
+}
+
+// Read the property `name`, terminated by a NUL byte, through the raw handle, e.g. when creating
+// an event. Any error is treated as the property being unavailable.
+fn ctx_property_node(ctx: NonNull<mpv_sys::mpv_handle>, name: &[u8]) -> Option<MpvNode> {
+    debug_assert_eq!(Some(&0), name.last());
+    MpvNode::get_from_c_void(|ptr| {
+        mpv_err((), unsafe {
+            mpv_sys::mpv_get_property(
+                ctx.as_ptr(),
+                name.as_ptr() as *const _,
+                mpv_format::Node,
+                ptr,
+            )
+        })
+    })
+    .ok()
+}
+
+// The data of a `FileLoaded` event of either event backend, read right after it arrived.
+pub(crate) struct FileLoadedInfo {
+    pub(crate) tracks: Vec<Track>,
+    pub(crate) duration: Option<f64>,
+    pub(crate) filename: String,
+}
+
+impl FileLoadedInfo {
+    pub(crate) fn from_ctx(ctx: NonNull<mpv_sys::mpv_handle>) -> FileLoadedInfo {
+        FileLoadedInfo {
+            tracks: Track::list_from_ctx(ctx),
+            duration: ctx_property_node(ctx, b"duration\0").and_then(|v| v.as_f64()),
+            filename: ctx_property_node(ctx, b"filename\0")
+                .and_then(|v| v.as_str().map(str::to_owned))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+// The result of the `MPV_EVENT_COMMAND_REPLY` `event`, which is the error of the command if it
+// failed.
+unsafe fn command_reply_result(event: &mpv_sys::mpv_event) -> Result<MpvNode> {
+    mpv_err((), event.error)?;
+    let command = event.data as *const mpv_sys::mpv_event_command;
+    if command.is_null() {
+        Ok(MpvNode::None)
+    } else {
+        MpvNode::from_raw(&(*command).result)
+    }
+}
+
+impl VideoParams {
+    // Read the `video-params` property, which is unavailable if there is no video.
+    fn from_ctx(ctx: NonNull<mpv_sys::mpv_handle>) -> Option<VideoParams> {
+        VideoParams::from_node(&ctx_property_node(ctx, b"video-params\0")?)
+    }
+
+    fn from_node(node: &MpvNode) -> Option<VideoParams> {
+        let params = node.as_map()?;
+        let i64_of = |key| params.get(key).and_then(MpvNode::as_i64).unwrap_or(0);
+
+        Some(VideoParams {
+            width: i64_of("w"),
+            height: i64_of("h"),
+            dwidth: i64_of("dw"),
+            dheight: i64_of("dh"),
+            aspect: params.get("aspect").and_then(MpvNode::as_f64).unwrap_or(0.),
+            rotate: i64_of("rotate"),
+            pixelformat: params
+                .get("pixelformat")
+                .and_then(MpvNode::as_str)
+                .unwrap_or("")
+                .to_owned(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A track of the current file, as returned by `Mpv::tracks` and carried by
+/// `Event::FileLoaded`.
+pub struct Track {
+    /// The id to select the track with, e.g. via `aid`, which is unique per `kind`.
+    pub id: i64,
+    /// `video`, `audio` or `sub`.
+    pub kind: String,
+    #[allow(missing_docs)]
+    pub title: Option<String>,
+    /// The language, e.g. `eng`.
+    pub lang: Option<String>,
+    /// The codec, e.g. `h264`.
+    pub codec: Option<String>,
+    /// Whether this track is currently selected.
+    pub selected: bool,
+    /// Whether this track is marked as default by the file.
+    pub default: bool,
+    /// Whether this track was loaded from an external file, e.g. by `Mpv::subtitle_add_select`.
+    pub external: bool,
+}
+
+impl Track {
+    // Read the `track-list` property, which is empty if there is no file.
+    fn list_from_ctx(ctx: NonNull<mpv_sys::mpv_handle>) -> Vec<Track> {
+        ctx_property_node(ctx, b"track-list\0")
+            .and_then(|node| Track::list_from_node(&node))
+            .unwrap_or_default()
+    }
+
+    fn list_from_node(node: &MpvNode) -> Option<Vec<Track>> {
+        node.as_array()?
+            .iter()
+            .map(|track| {
+                let track = track.as_map()?;
+                let str_of = |key| track.get(key).and_then(MpvNode::as_str).map(str::to_owned);
+                let bool_of = |key| track.get(key).and_then(MpvNode::as_bool).unwrap_or(false);
+                Some(Track {
+                    id: track.get("id").and_then(MpvNode::as_i64)?,
+                    kind: str_of("type")?,
+                    title: str_of("title"),
+                    lang: str_of("lang"),
+                    codec: str_of("codec"),
+                    selected: bool_of("selected"),
+                    default: bool_of("default"),
+                    external: bool_of("external"),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The dimensions of the current video, as returned by `Mpv::video_geometry`.
+pub struct VideoGeometry {
+    /// Width of the decoded video.
+    pub width: i64,
+    /// Height of the decoded video.
+    pub height: i64,
+    /// Width of the video after aspect ratio correction.
+    pub dwidth: i64,
+    /// Height of the video after aspect ratio correction.
+    pub dheight: i64,
+    /// Display aspect ratio of the video.
+    pub aspect: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A display the window is on, as listed by `Mpv::displays`.
+pub struct DisplayInfo {
+    /// The name of the display, e.g. `HDMI-A-1` on X11 or `\\.\DISPLAY1` on Windows.
+    pub name: String,
+    /// The refresh rate of the display, if known.
+    pub fps: Option<f64>,
+    /// The HiDPI scale factor of the display, if known.
+    pub hidpi_scale: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A screenshot in memory, as returned by `Mpv::screenshot_raw_rgba`.
+pub struct RawScreenshot {
+    #[allow(missing_docs)]
+    pub width: u32,
+    #[allow(missing_docs)]
+    pub height: u32,
+    /// The number of bytes per row, which is always `width * 4`.
+    pub stride: u32,
+    /// The pixels row by row, with one byte per channel in RGBA order.
+    pub data: Vec<u8>,
+}
+
+impl RawScreenshot {
+    // Convert the result of the `screenshot-raw` command.
+    pub(crate) fn from_node(node: &MpvNode) -> Result<RawScreenshot> {
+        let map = node.as_map().ok_or(Error::Raw(mpv_error::PropertyFormat))?;
+        let dimension = |key| {
+            map.get(key)
+                .and_then(MpvNode::as_i64)
+                .and_then(|v| usize::try_from(v).ok())
+                .ok_or(Error::Raw(mpv_error::PropertyFormat))
+        };
+        let (width, height, stride) = (dimension("w")?, dimension("h")?, dimension("stride")?);
+        let data = match map.get("data") {
+            Some(MpvNode::ByteArray(data)) => data,
+            _ => return Err(Error::Raw(mpv_error::PropertyFormat)),
+        };
+        let row = width.checked_mul(4);
+        let len = stride.checked_mul(height);
+        // The only format libmpv returns, the fourth byte is padding.
+        if map.get("format").and_then(MpvNode::as_str) != Some("bgr0")
+            || width == 0
+            || height == 0
+            || row.filter(|&row| stride >= row).is_none()
+            || len.filter(|&len| data.len() >= len).is_none()
+        {
+            return Err(Error::Raw(mpv_error::PropertyFormat));
+        }
+
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for row in data.chunks(stride).take(height) {
+            for px in row[..width * 4].chunks_exact(4) {
+                rgba.extend_from_slice(&[px[2], px[1], px[0], 0xff]);
+            }
+        }
+        Ok(RawScreenshot {
+            width: width as _,
+            height: height as _,
+            stride: (width * 4) as _,
+            data: rgba,
+        })
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<RawScreenshot> for image::DynamicImage {
+    fn from(screenshot: RawScreenshot) -> image::DynamicImage {
+        let image =
+            image::RgbaImage::from_raw(screenshot.width, screenshot.height, screenshot.data)
+                .expect("the data of a RawScreenshot fits its dimensions");
+        image::DynamicImage::ImageRgba8(image)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Values of the `--window-affinity` option, which is only supported on Windows.
+pub enum WindowAffinity {
+    /// Don't change the display affinity of the window.
+    Default,
+    /// Hide the window from screen capture.
+    ExcludeFromCapture,
+    /// Show the window only on a monitor, and black it out in screen capture.
+    Monitor,
+}
+
+impl WindowAffinity {
+    fn val(&self) -> &str {
+        match *self {
+            WindowAffinity::Default => "default",
+            WindowAffinity::ExcludeFromCapture => "excludefromcapture",
+            WindowAffinity::Monitor => "monitor",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// How `Mpv::set_video_filters` and `Mpv::set_audio_filters` change the filter chain.
+pub enum FilterOp {
+    /// Replace the filter chain.
+    Set,
+    /// Append the filters to the chain.
+    Add,
+    /// Add the filters if they aren't in the chain, remove them otherwise.
+    Toggle,
+    /// Remove the filters from the chain.
+    Remove,
+    /// Remove all filters, the spec is ignored.
+    Clear,
+}
+
+impl FilterOp {
+    fn val(&self) -> &str {
+        match *self {
+            FilterOp::Set => "set",
+            FilterOp::Add => "add",
+            FilterOp::Toggle => "toggle",
+            FilterOp::Remove => "remove",
+            FilterOp::Clear => "clr",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Values of the `--video-sync` option, i.e. how audio and video are kept in sync.
+pub enum VideoSyncMode {
+    /// Time the video to the audio.
+    Audio,
+    /// Resample the audio to match the video to the refresh rate of the display.
+    DisplayResample,
+    /// Like `DisplayResample`, but drop or repeat frames if the video falls out of sync.
+    DisplayResampleVdrop,
+    /// Like `DisplayResample`, but don't sync audio and video at all.
+    DisplayResampleDesync,
+    /// Drop or repeat audio data to match the video to the refresh rate of the display.
+    DisplayAdrop,
+    /// Drop or repeat video frames to match the video to the refresh rate of the display.
+    DisplayVdrop,
+    /// Time the video to the display, without syncing it with the audio.
+    DisplayDesync,
+    /// Time the video to the system clock, without syncing it with the audio.
+    Desync,
+}
+
+impl VideoSyncMode {
+    fn val(&self) -> &str {
+        match *self {
+            VideoSyncMode::Audio => "audio",
+            VideoSyncMode::DisplayResample => "display-resample",
+            VideoSyncMode::DisplayResampleVdrop => "display-resample-vdrop",
+            VideoSyncMode::DisplayResampleDesync => "display-resample-desync",
+            VideoSyncMode::DisplayAdrop => "display-adrop",
+            VideoSyncMode::DisplayVdrop => "display-vdrop",
+            VideoSyncMode::DisplayDesync => "display-desync",
+            VideoSyncMode::Desync => "desync",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A window geometry in the X11 `[WxH][+X+Y]` format.
+pub struct Geometry {
+    /// Width and height of the window.
+    pub size: Option<(u32, u32)>,
+    /// Offset of the window from the top left corner of the screen.
+    pub position: Option<(u32, u32)>,
+}
+
+impl FromStr for Geometry {
+    type Err = Error;
+
+    fn from_str(geom: &str) -> Result<Geometry> {
+        fn parse_pair(pair: &str, sep: char) -> Option<(u32, u32)> {
+            let mut split = pair.splitn(2, sep);
+            let (a, b) = (split.next()?, split.next()?);
+            if a.is_empty()
+                || b.is_empty()
+                || !a.bytes().chain(b.bytes()).all(|c| c.is_ascii_digit())
+            {
+                return None;
+            }
+            Some((a.parse().ok()?, b.parse().ok()?))
+        }
+
+        let invalid = || Error::InvalidGeometry(geom.to_owned());
+        let (size, position) = match geom.find('+') {
+            Some(idx) => (&geom[..idx], Some(&geom[idx + 1..])),
+            None => (geom, None),
+        };
+        if size.is_empty() && position.is_none() {
+            return Err(invalid());
+        }
+
+        Ok(Geometry {
+            size: if size.is_empty() {
+                None
+            } else {
+                Some(parse_pair(size, 'x').ok_or_else(invalid)?)
+            },
+            position: if let Some(position) = position {
+                Some(parse_pair(position, '+').ok_or_else(invalid)?)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((w, h)) = self.size {
+            write!(f, "{}x{}", w, h)?;
+        }
+        if let Some((x, y)) = self.position {
+            write!(f, "+{}+{}", x, y)?;
+        }
+        Ok(())
+    }
+}
+
+/// A builder for `Mpv`, used to set options that only take effect before initialization.
+pub struct MpvBuilder {
+    ctx: NonNull<mpv_sys::mpv_handle>,
+}
+
+unsafe impl Send for MpvBuilder {}
+
+impl Drop for MpvBuilder {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            mpv_sys::mpv_terminate_destroy(self.ctx.as_ptr());
+        }
+    }
+}
+
+impl MpvBuilder {
+    #[inline]
+    /// Create a new, uninitialized mpv core.
+    pub fn new() -> Result<MpvBuilder> {
+        let api_version = unsafe { mpv_sys::mpv_client_api_version() };
+        if crate::MPV_CLIENT_API_VERSION != api_version {
+            return Err(Error::VersionMismatch {
+                linked: crate::MPV_CLIENT_API_VERSION,
+                loaded: api_version,
+            });
+        }
+
+        let ctx = unsafe { mpv_sys::mpv_create() };
+        if ctx.is_null() {
+            return Err(Error::Null);
+        }
+
+        Ok(MpvBuilder {
+            ctx: unsafe { NonNull::new_unchecked(ctx) },
+        })
+    }
+
+    #[inline]
+    /// Set the value of an option, this is the equivalent of passing `--name=data` to mpv.
+    pub fn set_option<T: SetData>(self, name: &str, data: T) -> Result<MpvBuilder> {
+        let name = CString::new(name)?;
+        let format = T::get_format().as_mpv_format().as_raw();
+        data.call_as_c_void(|ptr| {
+            mpv_err((), unsafe {
+                mpv_sys::mpv_set_option(self.ctx.as_ptr(), name.as_ptr(), format, ptr)
+            })
+        })?;
+        Ok(self)
+    }
+
+    #[inline]
+    /// Set the number of threads used for video decoding (`--vd-lavc-threads`).
+    ///
+    /// `0` lets mpv autodetect the number of cores.
+    pub fn video_decoder_threads(self, n: u32) -> Result<MpvBuilder> {
+        self.set_option("vd-lavc-threads", i64::from(n))
+    }
+
+    #[inline]
+    /// Set the number of threads used for audio decoding (`--ad-lavc-threads`).
+    ///
+    /// `0` lets mpv autodetect the number of cores.
+    pub fn audio_decoder_threads(self, n: u32) -> Result<MpvBuilder> {
+        self.set_option("ad-lavc-threads", i64::from(n))
+    }
+
+    #[inline]
+    /// Only use bit-exact algorithms in video decoding (`--vd-lavc-bitexact`), so that the
+    /// decoded output is identical across runs.
+    pub fn video_bitexact(self, enable: bool) -> Result<MpvBuilder> {
+        self.set_option("vd-lavc-bitexact", enable)
+    }
+
+    #[inline]
+    /// Set how much of a stream is analyzed when probing it, in microseconds
+    /// (`--demuxer-lavf-analyzeduration`).
+    ///
+    /// Lower values speed up opening files, but may cause the format detection to fail.
+    pub fn analyze_duration(self, us: u64) -> Result<MpvBuilder> {
+        self.set_option("demuxer-lavf-analyzeduration", us as f64 / 1_000_000.)
+    }
+
+    #[inline]
+    /// Set how many bytes of a stream are read when probing it (`--demuxer-lavf-probesize`).
+    ///
+    /// Lower values speed up opening files, but may cause the format detection to fail.
+    pub fn probe_size(self, bytes: u64) -> Result<MpvBuilder> {
+        let bytes = i64::try_from(bytes).map_err(|_| Error::InvalidArgument)?;
+        self.set_option("demuxer-lavf-probesize", bytes)
+    }
+
+    /// Take the handle out of the builder, without destroying it.
+    fn into_raw(self) -> *mut mpv_sys::mpv_handle {
+        let ctx = self.ctx.as_ptr();
+        std::mem::forget(self);
+        ctx
+    }
+
+    #[inline]
+    /// Initialize the mpv core, and create the `Mpv`.
+    ///
+    /// With `events_sync`, this disables all events.
+    pub fn build(self) -> Result<Mpv> {
+        let mpv = Mpv::from_ctx(unsafe { NonNull::new_unchecked(self.into_raw()) }, true)?;
+        mpv_err((), unsafe { mpv_sys::mpv_initialize(mpv.ctx.as_ptr()) })?;
+        Ok(mpv)
+    }
+}
+
+/// Commands that are run in order by `run`, see `Mpv::batch`.
+pub struct CommandBatch<'a> {
+    mpv: &'a Mpv,
+    commands: Vec<&'a [&'a str]>,
+}
+
+impl<'a> CommandBatch<'a> {
+    #[inline]
+    /// Add a command, given as its name followed by its arguments like `Mpv::command_blocking`.
+    pub fn command(mut self, args: &'a [&'a str]) -> CommandBatch<'a> {
+        self.commands.push(args);
+        self
+    }
+
+    #[inline]
+    /// Run the commands in order, stopping at the first error, which is returned as
+    /// `Error::Batch` with the index of the failed command.
+    pub fn run(self) -> Result<()> {
+        for (index, args) in self.commands.into_iter().enumerate() {
+            self.mpv.command_ret(args).map_err(|e| Error::Batch {
+                index,
+                error: ::std::rc::Rc::new(e),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A builder for an `Mpv` in encoding mode, which writes the played files to an output file
+/// instead of playing them (`--o`).
+///
+/// The encoding options are only available here, as they can't be changed after
+/// initialization.
+pub struct EncodeBuilder {
+    builder: MpvBuilder,
+}
+
+impl EncodeBuilder {
+    #[inline]
+    /// Create a new, uninitialized mpv core that encodes to `output`.
+    ///
+    /// The container format is guessed from the extension of `output`, see `format`.
+    pub fn new(output: &str) -> Result<EncodeBuilder> {
+        Ok(EncodeBuilder {
+            builder: MpvBuilder::new()?.set_option("o", output)?,
+        })
+    }
+
+    #[inline]
+    /// Set the value of any other option, see `MpvBuilder::set_option`.
+    pub fn set_option<T: SetData>(self, name: &str, data: T) -> Result<EncodeBuilder> {
+        Ok(EncodeBuilder {
+            builder: self.builder.set_option(name, data)?,
+        })
+    }
+
+    #[inline]
+    /// Set the container format of the output, e.g. `matroska` (`--of`).
+    pub fn format(self, format: &str) -> Result<EncodeBuilder> {
+        self.set_option("of", format)
+    }
+
+    #[inline]
+    /// Set the video codec, e.g. `libx264` (`--ovc`).
+    pub fn video_codec(self, codec: &str) -> Result<EncodeBuilder> {
+        self.set_option("ovc", codec)
+    }
+
+    #[inline]
+    /// Set the audio codec, e.g. `aac` (`--oac`).
+    pub fn audio_codec(self, codec: &str) -> Result<EncodeBuilder> {
+        self.set_option("oac", codec)
+    }
+
+    #[inline]
+    /// Set options of the video codec, e.g. `[("crf", "23")]` (`--ovcopts`).
+    pub fn video_codec_options(self, options: &[(&str, &str)]) -> Result<EncodeBuilder> {
+        let options = key_value_list(options)?;
+        self.set_option("ovcopts", &*options)
+    }
+
+    #[inline]
+    /// Set options of the audio codec, e.g. `[("b", "128k")]` (`--oacopts`).
+    pub fn audio_codec_options(self, options: &[(&str, &str)]) -> Result<EncodeBuilder> {
+        let options = key_value_list(options)?;
+        self.set_option("oacopts", &*options)
+    }
+
+    #[inline]
+    /// Set the video filter chain, e.g. `scale=1280:-2` (`--vf`).
+    pub fn video_filters(self, filters: &str) -> Result<EncodeBuilder> {
+        self.set_option("vf", filters)
+    }
+
+    #[inline]
+    /// Set the audio filter chain (`--af`).
+    pub fn audio_filters(self, filters: &str) -> Result<EncodeBuilder> {
+        self.set_option("af", filters)
+    }
+
+    #[inline]
+    /// Initialize the mpv core. The files to encode are added with e.g. `Mpv::load_file`, and
+    /// `Mpv::wait_for_encode` waits for them to be done.
+    pub fn build(self) -> Result<Mpv> {
+        self.builder.build()
+    }
+}
+
+pub struct Mpv {
+    /// The handle to the mpv core
+    pub ctx: NonNull<mpv_sys::mpv_handle>,
+    #[cfg(feature = "events_sync")]
+    ev_iter_notification: std::sync::Arc<(Mutex<bool>, parking_lot::Condvar)>,
+    #[cfg(feature = "events_sync")]
+    ev_to_observe: Mutex<Vec<events::sync::Event>>,
+    #[cfg(feature = "events_sync")]
+    ev_to_observe_properties: Mutex<::std::collections::HashMap<String, u64>>,
+    #[cfg(feature = "events_sync")]
+    ev_observed: Mutex<Vec<events::sync::Event>>,
+    // Set once an `EventIter` received `Event::Shutdown`, so that all others yield it as well.
+    #[cfg(feature = "events_sync")]
+    ev_shutdown: AtomicBool,
+    #[cfg(feature = "protocols")]
+    protocols_guard: AtomicBool,
+    wakeup_callback: parking_lot::Mutex<Option<Box<WakeupCallback>>>,
+    log_level: AtomicU32,
+    // The input sections defined by `bind_key`, by key.
+    key_sections: parking_lot::Mutex<HashMap<String, String>>,
+    // Whether dropping this terminates the core, which clones don't do.
+    terminate: bool,
+}
+
+type WakeupCallback = Box<dyn Fn() + Send>;
+
+/// Wakes up the event loop of an application, e.g. of winit or gtk, whenever mpv has new events.
+/// Installed with `Mpv::set_event_driver`.
+pub trait EventDriver: Send + Sync + 'static {
+    /// Called whenever there are new events, after which the application should poll for them.
+    ///
+    /// This may be called from any thread, and must not call any mpv API itself.
+    fn wake(&self);
+}
+
+unsafe extern "C" fn wakeup_callback(d: *mut ctype::c_void) {
+    (*(d as *mut WakeupCallback))();
+}
+
+unsafe impl Send for Mpv {}
+unsafe impl Sync for Mpv {}
+
+impl Drop for Mpv {
+    #[inline]
+    fn drop(&mut self) {
+        // The wakeup callback points into `self`, e.g. the condvar of `events_sync`, so make sure
+        // that mpv can't call it anymore, even while shutting down.
+        unsafe {
+            mpv_sys::mpv_set_wakeup_callback(self.ctx.as_ptr(), None, ptr::null_mut());
+            if self.terminate {
+                mpv_sys::mpv_terminate_destroy(self.ctx.as_ptr());
+            } else {
+                mpv_sys::mpv_destroy(self.ctx.as_ptr());
+            }
+        }
+    }
+}
+
+/// Cloning creates a new client of the same mpv core with `mpv_create_client`, not a second
+/// player. The clone has its own event queue, wakeup callback, and key bindings, and can be
+/// dropped independently; dropping the original `Mpv` still terminates the core for all clones.
+///
+/// # Panics
+/// If libmpv fails to create the client, e.g. because the core is shutting down.
+impl Clone for Mpv {
+    fn clone(&self) -> Mpv {
+        let name = CString::new("clone").unwrap();
+        let ctx = unsafe { mpv_sys::mpv_create_client(self.ctx.as_ptr(), name.as_ptr()) };
+        let ctx = NonNull::new(ctx).expect("failed to create mpv client");
+        Mpv::from_ctx(ctx, false).expect("failed to set up mpv client")
+    }
+}
+
+impl Mpv {
+    #[cfg(not(feature = "events_sync"))]
+    #[inline]
+    /// Create a new `Mpv`.
+    /// The default settings can be probed by running: `$ mpv --show-profile=libmpv`
+    pub fn new() -> Result<Mpv> {
+        MpvBuilder::new()?.build()
+    }
+
+    #[cfg(not(feature = "events_sync"))]
+    /// Take ownership of `ctx`, which is destroyed when the `Mpv` is dropped, terminating the
+    /// core if `terminate` is set.
+    fn from_ctx(ctx: NonNull<mpv_sys::mpv_handle>, terminate: bool) -> Result<Mpv> {
+        Ok(Mpv {
+            ctx,
+            #[cfg(feature = "protocols")]
+            protocols_guard: AtomicBool::new(false),
+            wakeup_callback: parking_lot::Mutex::new(None),
+            log_level: AtomicU32::new(mpv_log_level::None),
+            key_sections: parking_lot::Mutex::new(HashMap::new()),
+            terminate,
+        })
+    }
+
+    #[inline]
+    /// The raw handle of the mpv core, e.g. to create a render context with `mpv_sys`.
+    ///
+    /// The handle is owned by this `Mpv`, so it must not be destroyed, and it must not be used
+    /// after the `Mpv` is dropped. Changing the wakeup callback, or waiting for events while this
+    /// crate does so, breaks the `Mpv` in unspecified ways.
+    pub fn raw_handle(&self) -> *mut mpv_sys::mpv_handle {
+        self.ctx.as_ptr()
+    }
+
+    /// Install `callback` as the wakeup callback of mpv, replacing the previous one.
+    fn set_raw_wakeup_callback(&self, callback: WakeupCallback) {
+        // Hold the lock until mpv switched over, so that the previous callback is not freed
+        // while it can still be called.
+        let mut current = self.wakeup_callback.lock();
+        let mut callback = Box::new(callback);
+        unsafe {
+            mpv_sys::mpv_set_wakeup_callback(
+                self.ctx.as_ptr(),
+                Some(wakeup_callback),
+                &mut *callback as *mut WakeupCallback as *mut _,
+            );
+        }
+        *current = Some(callback);
+    }
+
+    #[cfg(not(feature = "events_sync"))]
+    #[inline]
+    /// Set a callback that is called whenever there are new events, e.g. to wake up an event loop
+    /// that then calls `wait_event` with a timeout of `0`.
+    ///
+    /// The callback may be called from any thread, and must not call any mpv API itself.
+    pub fn set_wakeup_callback<F: Fn() + Send + 'static>(&self, f: F) {
+        self.set_raw_wakeup_callback(Box::new(f));
+    }
+
+    #[cfg(not(feature = "events_sync"))]
+    #[inline]
+    /// Let `driver` wake up the event loop of the application whenever there are new events,
+    /// which then calls `wait_event` with a timeout of `0`. This replaces the wakeup callback.
+    pub fn set_event_driver<D: EventDriver>(&self, driver: D) {
+        self.set_raw_wakeup_callback(Box::new(move || driver.wake()));
+    }
+
+    #[inline]
+    /// Set the minimum level of log messages that are received, `None` disables them.
+    ///
+    /// With `events_sync`, a dropped `EventIter` that observed `Event::LogMessage` doesn't disable
+    /// log messages, if the level was changed by this in the meantime.
+    pub fn set_log_level(&self, level: Option<LogLevel>) -> Result<()> {
+        let level = level.unwrap_or(LogLevel::None);
+        let min_level = CString::new(level.to_string())?;
+        mpv_err((), unsafe {
+            mpv_sys::mpv_request_log_messages(self.ctx.as_ptr(), min_level.as_ptr())
+        })?;
+        self.log_level.store(level.as_raw(), Ordering::SeqCst);
+        Ok(())
+    }
+
+    #[inline]
+    /// Load a configuration file. The path has to be absolute, and a file.
+    pub fn load_config(&self, path: &str) -> Result<()> {
+        let file = CString::new(path)?.into_raw();
+        let ret = mpv_err((), unsafe {
+            mpv_sys::mpv_load_config_file(self.ctx.as_ptr(), file)
+        });
+        unsafe { CString::from_raw(file) };
+        ret
+    }
+
+    #[inline]
+    /// Send a command to the `Mpv` instance. This uses `mpv_command_string` internally,
+    /// so that the syntax is the same as described in the [manual for the input.conf]
+    /// (https://mpv.io/manual/master/#list-of-input-commands).
+    ///
+    /// Note that you may have to escape strings with `""` when they contain spaces.
+    pub fn command(&self, name: &str, args: &[&str]) -> Result<()> {
+        let mut cmd =
+            String::with_capacity(name.len() + args.iter().fold(0, |acc, e| acc + e.len() + 1));
+        cmd.push_str(name);
+
+        for elem in args {
+            cmd.push_str(" ");
+            cmd.push_str(elem);
+        }
+        self.command_string(&cmd)
+    }
+
+    #[inline]
+    /// Run a single line in the input.conf syntax, e.g. `seek 10 exact`, which is passed to
+    /// `mpv_command_string` as is.
+    ///
+    /// mpv does the tokenization itself, so quoting and chaining commands with `;` work the same
+    /// as in input.conf.
+    pub fn command_string(&self, command: &str) -> Result<()> {
+        let raw = CString::new(command)?;
+        mpv_err((), unsafe {
+            mpv_sys::mpv_command_string(self.ctx.as_ptr(), raw.as_ptr())
+        })
+    }
+
+    #[inline]
+    /// Start a batch of commands, which are run in order, e.g.
+    /// `mpv.batch().command(&["set", "pause", "yes"]).command(&["seek", "0", "absolute"]).run()`.
+    pub fn batch(&self) -> CommandBatch<'_> {
+        CommandBatch {
+            mpv: self,
+            commands: Vec::new(),
+        }
+    }
+
+    // Run a command given as separate arguments, and return its result.
+    fn command_ret(&self, args: &[&str]) -> Result<MpvNode> {
+        self.command_node(args, |node| unsafe { MpvNode::from_raw(node) })
+    }
+
+    #[inline]
+    /// Like `command_ret`, but walk the result with `visitor` instead of copying it, which is
+    /// cheaper if only a part of a large result is needed.
+    pub fn command_visit<V: NodeVisitor>(&self, args: &[&str], visitor: &mut V) -> Result<()> {
+        self.command_node(args, |node| unsafe { MpvNode::visit_raw(node, visitor) })
+    }
+
+    // Run a command via `mpv_command_node`, passing its result to `f` before it's freed.
+    fn command_node<T, F: FnOnce(&mpv_sys::mpv_node) -> Result<T>>(
+        &self,
+        args: &[&str],
+        f: F,
+    ) -> Result<T> {
+        let args = args
+            .iter()
+            .map(|arg| CString::new(*arg))
+            .collect::<::std::result::Result<Vec<_>, _>>()?;
+        let mut values = args
+            .iter()
+            .map(|arg| mpv_sys::mpv_node {
+                u: mpv_sys::mpv_node__bindgen_ty_1 {
+                    string: arg.as_ptr() as *mut _,
+                },
+                format: mpv_format::String,
+            })
+            .collect::<Vec<_>>();
+        let mut list = mpv_sys::mpv_node_list {
+            num: values.len() as _,
+            values: values.as_mut_ptr(),
+            keys: ptr::null_mut(),
+        };
+        let mut node = mpv_sys::mpv_node {
+            u: mpv_sys::mpv_node__bindgen_ty_1 { list: &mut list },
+            format: mpv_format::NodeArray,
+        };
+
+        let mut result = MaybeUninit::uninit();
+        mpv_err((), unsafe {
+            mpv_sys::mpv_command_node(self.ctx.as_ptr(), &mut node, result.as_mut_ptr())
+        })?;
+
+        let mut result = unsafe { result.assume_init() };
+        let ret = f(&result);
+        unsafe { mpv_sys::mpv_free_node_contents(&mut result) };
+        ret
+    }
+
+    #[inline]
+    /// Run the command `name` with `args` asynchronously. Its result is delivered by an
+    /// `Event::CommandReply` with `reply_id`, e.g. the output of a `subprocess`.
+    ///
+    /// Unlike `command`, the arguments can be of any type, e.g. a `Map` of named arguments.
+    /// They are copied by libmpv, so they only need to live for the duration of this call.
+    pub fn command_node_async(&self, reply_id: u64, name: &str, args: &[MpvNode]) -> Result<()> {
+        let mut storage = RawNodeStorage::default();
+        let mut values = Some(MpvNode::String(name.to_owned()))
+            .iter()
+            .chain(args)
+            .map(|arg| arg.to_raw(&mut storage))
+            .collect::<Result<Vec<_>>>()?;
+        let list = storage.push_list(&mut values, ptr::null_mut());
+        let mut node = mpv_sys::mpv_node {
+            u: mpv_sys::mpv_node__bindgen_ty_1 { list },
+            format: mpv_format::NodeArray,
+        };
+
+        mpv_err((), unsafe {
+            mpv_sys::mpv_command_node_async(self.ctx.as_ptr(), reply_id, &mut node)
+        })
+    }
+
+    #[inline]
+    /// Run a command asynchronously, and wait at most `timeout` for its reply.
+    /// `args` contains the command name, followed by its arguments, which are not parsed further.
+    ///
+    /// This is useful for commands that do real work, as the calling thread is not blocked
+    /// uninterruptibly. Returns `Error::Timeout` if there was no reply in time, in which case the
+    /// command is still run, but its result is discarded.
+    pub fn command_blocking(&self, args: &[&str], timeout: Duration) -> Result<MpvNode> {
+        let client = SubClient::new(self)?;
+
+        let args = args
+            .iter()
+            .map(|arg| CString::new(*arg))
+            .collect::<::std::result::Result<Vec<_>, _>>()?;
+        let mut raw_args = args
+            .iter()
+            .map(|arg| arg.as_ptr())
+            .chain(Some(ptr::null()))
+            .collect::<Vec<_>>();
+
+        let id = NEXT_REPLY_ID.fetch_add(1, Ordering::Relaxed);
+        mpv_err((), unsafe {
+            mpv_sys::mpv_command_async(client.0.as_ptr(), id, raw_args.as_mut_ptr())
+        })?;
+
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            let event =
+                unsafe { &*mpv_sys::mpv_wait_event(client.0.as_ptr(), wait_timeout(deadline)?) };
+            if event.event_id == events::mpv_event_id::CommandReply && event.reply_userdata == id {
+                return unsafe { command_reply_result(event) };
+            }
+        }
+    }
+
+    #[inline]
+    /// Block until the property `name` equals `target`, or at most `timeout`.
+    ///
+    /// Returns immediately if the property already equals `target`, and `Error::Timeout` if it
+    /// didn't change to `target` in time. An unavailable property never equals `target`.
+    pub fn wait_for_property<T: PartialEq + GetData>(
+        &self,
+        name: &str,
+        target: T,
+        timeout: Duration,
+    ) -> Result<()> {
+        if self.get_property_opt::<T>(name)?.as_ref() == Some(&target) {
+            return Ok(());
+        }
+
+        let client = SubClient::new(self)?;
+        let raw_name = CString::new(name)?;
+        let id = NEXT_REPLY_ID.fetch_add(1, Ordering::Relaxed);
+        // Only used as a change notification, the value is read with the right type below.
+        mpv_err((), unsafe {
+            mpv_sys::mpv_observe_property(
+                client.0.as_ptr(),
+                id,
+                raw_name.as_ptr(),
+                mpv_format::None,
+            )
+        })?;
+
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            let event =
+                unsafe { &*mpv_sys::mpv_wait_event(client.0.as_ptr(), wait_timeout(deadline)?) };
+            if event.event_id == events::mpv_event_id::PropertyChange
+                && event.reply_userdata == id
+                && self.get_property_opt::<T>(name)?.as_ref() == Some(&target)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    #[inline]
+    /// Block until the current file was encoded completely, see `EncodeBuilder`.
+    ///
+    /// This only sees the end of files that end after it was called, so call it right after
+    /// loading the file. Returns the error of libmpv if encoding failed, and `Error::CommandFailed`
+    /// if it was stopped before the end of the file.
+    pub fn wait_for_encode(&self) -> Result<()> {
+        let client = SubClient::new(self)?;
+        loop {
+            let event = unsafe { &*mpv_sys::mpv_wait_event(client.0.as_ptr(), -1.) };
+            match event.event_id {
+                events::mpv_event_id::EndFile => {
+                    let end_file = event.data as *const mpv_sys::mpv_event_end_file;
+                    match EndFileReason::from_raw(unsafe { (*end_file).reason }) {
+                        EndFileReason::Eof => return Ok(()),
+                        EndFileReason::Error => {
+                            return mpv_err((), unsafe { (*end_file).error });
+                        }
+                        // The playlist entries replacing this file are encoded next.
+                        EndFileReason::Redirect => {}
+                        _ => return Err(Error::CommandFailed),
+                    }
+                }
+                events::mpv_event_id::Shutdown => return Err(Error::CommandFailed),
+                _ => {}
+            }
+        }
+    }
+
+    #[inline]
+    /// Set the value of a property.
+    pub fn set_property<T: SetData>(&self, name: &str, data: T) -> Result<()> {
+        let name = CString::new(name)?;
+        let format = T::get_format().as_mpv_format().as_raw();
+        data.call_as_c_void(|ptr| {
+            mpv_err((), unsafe {
+                mpv_sys::mpv_set_property(self.ctx.as_ptr(), name.as_ptr(), format, ptr)
+            })
+        })
+    }
+
+    #[inline]
+    #[cfg(any(feature = "events_simple", feature = "events_sync"))]
+    /// Set the given properties in order, stopping at the first error, which is returned as
+    /// `Error::SetProperty` with the name of the failed property.
+    ///
+    /// This is not atomic, as libmpv has no transactions: the properties set before the failed
+    /// one keep their new values.
+    pub fn set_properties(&self, props: &[(&str, PropertyData)]) -> Result<()> {
+        for &(name, ref data) in props {
+            data.set_on(self, name).map_err(|e| Error::SetProperty {
+                name: name.to_owned(),
+                error: ::std::rc::Rc::new(e),
+            })?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    /// Get the value of a property.
     pub fn get_property<T: GetData>(&self, name: &str) -> Result<T> {
         let name = CString::new(name)?;
 
-        let format = T::get_format().as_mpv_format() as _;
+        let format = T::get_format().as_mpv_format().as_raw();
         T::get_from_c_void(|ptr| {
             mpv_err((), unsafe {
                 mpv_sys::mpv_get_property(self.ctx.as_ptr(), name.as_ptr(), format, ptr)
@@ -426,6 +2064,94 @@ impl Mpv {
         })
     }
 
+    #[inline]
+    /// Get the value of any property as a string, e.g. `100.000000` for `volume`.
+    pub fn get_property_string(&self, name: &str) -> Result<String> {
+        self.get_property_as_string(name, MpvFormat::String)
+    }
+
+    #[inline]
+    /// Get the value of a property formatted for display on the OSD, e.g. `00:03:42` for
+    /// `time-pos`.
+    pub fn get_property_osd(&self, name: &str) -> Result<String> {
+        self.get_property_as_string(name, MpvFormat::OsdString)
+    }
+
+    #[inline]
+    /// Get the value of an integer property that is really an enum, e.g. `video-rotate`.
+    ///
+    /// Returns `Error::Raw(mpv_error::PropertyFormat)` if the value doesn't convert into `E`.
+    pub fn get_property_enum<E: TryFrom<i64>>(&self, name: &str) -> Result<E> {
+        E::try_from(self.get_property::<i64>(name)?)
+            .map_err(|_| Error::Raw(mpv_error::PropertyFormat))
+    }
+
+    #[inline]
+    /// Walk the value of a property with `visitor` instead of copying it, e.g. to read only a few
+    /// fields of every entry of `track-list`.
+    pub fn get_property_visit<V: NodeVisitor>(&self, name: &str, visitor: &mut V) -> Result<()> {
+        let name = CString::new(name)?;
+
+        let mut node = MaybeUninit::<mpv_sys::mpv_node>::uninit();
+        mpv_err((), unsafe {
+            mpv_sys::mpv_get_property(
+                self.ctx.as_ptr(),
+                name.as_ptr(),
+                mpv_format::Node,
+                node.as_mut_ptr() as *mut _,
+            )
+        })?;
+
+        let mut node = unsafe { node.assume_init() };
+        let ret = unsafe { MpvNode::visit_raw(&node, visitor) };
+        unsafe { mpv_sys::mpv_free_node_contents(&mut node) };
+        ret
+    }
+
+    #[inline]
+    /// The names of all properties (`property-list`), e.g. for autocompletion.
+    pub fn property_names(&self) -> Result<Vec<String>> {
+        let mut names = StringList(Vec::new());
+        self.get_property_visit("property-list", &mut names)?;
+        Ok(names.0)
+    }
+
+    #[inline]
+    /// The names of all options (`options`), without the leading `--`.
+    pub fn option_names(&self) -> Result<Vec<String>> {
+        let mut names = StringList(Vec::new());
+        self.get_property_visit("options", &mut names)?;
+        Ok(names.0)
+    }
+
+    // Get a property as `MPV_FORMAT_STRING` or `MPV_FORMAT_OSD_STRING`.
+    fn get_property_as_string(&self, name: &str, format: MpvFormat) -> Result<String> {
+        let name = CString::new(name)?;
+
+        let mut ptr: *mut ctype::c_char = ptr::null_mut();
+        mpv_err((), unsafe {
+            mpv_sys::mpv_get_property(
+                self.ctx.as_ptr(),
+                name.as_ptr(),
+                format.as_raw(),
+                &mut ptr as *mut *mut ctype::c_char as *mut _,
+            )
+        })?;
+
+        let ret = unsafe { mpv_cstr_to_str!(ptr) }.map(str::to_owned);
+        unsafe { mpv_sys::mpv_free(ptr as *mut _) };
+        ret
+    }
+
+    // Like `get_property`, but an unavailable property is `None` instead of an error.
+    fn get_property_opt<T: GetData>(&self, name: &str) -> Result<Option<T>> {
+        match self.get_property(name) {
+            Ok(v) => Ok(Some(v)),
+            Err(Error::Raw(mpv_error::PropertyUnavailable)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     #[inline]
     /// Internal time in microseconds, this has an arbitrary offset, and will never go backwards.
     ///
@@ -439,21 +2165,177 @@ impl Mpv {
 
     #[inline]
     /// Add -or subtract- any value from a property. Over/underflow clamps to max/min.
-    pub fn add_property(&self, property: &str, value: isize) -> Result<()> {
-        self.command("add", &[property, &format!("{}", value)])
+    ///
+    /// Returns `Error::InvalidArgument` if `value` isn't finite.
+    pub fn add_property(&self, property: &str, value: f64) -> Result<()> {
+        if !value.is_finite() {
+            return Err(Error::InvalidArgument);
+        }
+        self.command_ret(&["add", property, &value.to_string()])
+            .map(|_| ())
     }
 
     #[inline]
-    /// Cycle through a given property. `up` specifies direction. On
+    /// Cycle through a given property in `direction`. On
     /// overflow, set the property back to the minimum, on underflow set it to the maximum.
-    pub fn cycle_property(&self, property: &str, up: bool) -> Result<()> {
-        self.command("cycle", &[property, if up { "up" } else { "down" }])
+    pub fn cycle_property(&self, property: &str, direction: CycleDirection) -> Result<()> {
+        self.command_ret(&["cycle", property, direction.val()])
+            .map(|_| ())
     }
 
     #[inline]
     /// Multiply any property with any positive factor.
-    pub fn multiply_property(&self, property: &str, factor: usize) -> Result<()> {
-        self.command("multiply", &[property, &format!("{}", factor)])
+    ///
+    /// Returns `Error::InvalidArgument` if `factor` isn't positive or not finite.
+    pub fn multiply_property(&self, property: &str, factor: f64) -> Result<()> {
+        if !factor.is_finite() || factor <= 0. {
+            return Err(Error::InvalidArgument);
+        }
+        self.command_ret(&["multiply", property, &factor.to_string()])
+            .map(|_| ())
+    }
+
+    #[inline]
+    /// Read the commonly displayed playback properties at once, e.g. to render the initial state
+    /// of a UI.
+    pub fn playback_state(&self) -> Result<PlaybackState> {
+        Ok(PlaybackState {
+            pause: self.get_property("pause")?,
+            time_pos: self.get_property_opt("time-pos")?,
+            duration: self.get_property_opt("duration")?,
+            volume: self.get_property("volume")?,
+            mute: self.get_property("mute")?,
+            media_title: self.get_property_opt("media-title")?,
+            eof_reached: self.get_property_opt("eof-reached")?,
+        })
+    }
+
+    #[inline]
+    /// Whether playback reached the end of the file (`eof-reached`), `false` if no file is
+    /// loaded.
+    pub fn is_eof(&self) -> Result<bool> {
+        Ok(self.get_property_opt("eof-reached")?.unwrap_or(false))
+    }
+
+    #[inline]
+    /// Whether the player is idle, i.e. no file is loaded (`idle-active`), `false` if unknown.
+    pub fn is_idle(&self) -> Result<bool> {
+        Ok(self.get_property_opt("idle-active")?.unwrap_or(false))
+    }
+
+    #[inline]
+    /// Read the buffering state of the demuxer cache, e.g. to show progress while streaming.
+    pub fn cache_state(&self) -> Result<CacheState> {
+        let cache_duration = match self.get_property_opt("demuxer-cache-duration")? {
+            Some(duration) => Some(duration),
+            None => self
+                .get_property_opt::<MpvNode>("demuxer-cache-state")?
+                .and_then(|state| {
+                    state
+                        .as_map()
+                        .and_then(|state| state.get("cache-duration"))
+                        .and_then(MpvNode::as_f64)
+                }),
+        };
+
+        Ok(CacheState {
+            buffering_percent: self.get_property_opt("cache-buffering-state")?,
+            paused_for_cache: self.get_property_opt("paused-for-cache")?.unwrap_or(false),
+            cache_duration,
+        })
+    }
+
+    #[inline]
+    /// Read the details of the demuxer cache from `demuxer-cache-state`.
+    ///
+    /// Returns `Error::Raw(mpv_error::PropertyUnavailable)` if there is no cache, e.g. because no
+    /// file is playing. Values missing from the cache state are `0` or `false`.
+    pub fn cache_status(&self) -> Result<CacheStatus> {
+        let state = self.get_property::<MpvNode>("demuxer-cache-state")?;
+        let state = state
+            .as_map()
+            .ok_or(Error::Raw(mpv_error::PropertyFormat))?;
+        let f64_of = |key| state.get(key).and_then(MpvNode::as_f64);
+
+        // Measured from the start of the cached range that contains the playback position.
+        let backward_secs = match (f64_of("reader-pts"), state.get("seekable-ranges")) {
+            (Some(pts), Some(MpvNode::Array(ranges))) => ranges
+                .iter()
+                .filter_map(|range| {
+                    let range = range.as_map()?;
+                    let start = range.get("start").and_then(MpvNode::as_f64)?;
+                    let end = range.get("end").and_then(MpvNode::as_f64)?;
+                    Some((start, end))
+                })
+                .find(|&(start, end)| start <= pts && pts <= end)
+                .map_or(0., |(start, _)| pts - start),
+            _ => 0.,
+        };
+        let speed_bytes_per_sec = match self.get_property_opt("cache-speed")? {
+            Some(speed) => speed,
+            // Fall back to the input rate of the cache state.
+            None => f64_of("raw-input-rate").unwrap_or(0.),
+        };
+
+        Ok(CacheStatus {
+            speed_bytes_per_sec,
+            buffering_percent: self
+                .get_property_opt::<i64>("cache-buffering-state")?
+                .unwrap_or(0) as f64,
+            forward_secs: f64_of("cache-duration").unwrap_or(0.),
+            backward_secs,
+            underrun: state
+                .get("underrun")
+                .and_then(MpvNode::as_bool)
+                .unwrap_or(false),
+        })
+    }
+
+    #[inline]
+    /// The tags of the current file (`metadata`), which is empty if no file is loaded.
+    ///
+    /// See `Metadata` for accessors of common tags.
+    pub fn metadata(&self) -> Result<HashMap<String, String>> {
+        self.get_string_map("metadata")
+    }
+
+    #[inline]
+    /// Like `metadata`, but only the tags selected by `--display-tags` (`filtered-metadata`).
+    pub fn filtered_metadata(&self) -> Result<HashMap<String, String>> {
+        self.get_string_map("filtered-metadata")
+    }
+
+    #[inline]
+    /// The tags of the chapter with index `chapter` (`chapter-list/N`). libmpv only exposes the
+    /// title of chapters other than the current one, so this contains at most `title`.
+    pub fn chapter_metadata(&self, chapter: usize) -> Result<HashMap<String, String>> {
+        self.get_string_map(&format!("chapter-list/{}", chapter))
+    }
+
+    #[inline]
+    /// The video, audio and subtitle tracks of the current file (`track-list`), which is empty if
+    /// no file is loaded.
+    pub fn tracks(&self) -> Result<Vec<Track>> {
+        match self.get_property_opt::<MpvNode>("track-list")? {
+            Some(node) => Track::list_from_node(&node).ok_or(Error::Raw(mpv_error::PropertyFormat)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    // Read the string entries of a `NodeMap` property, an unavailable property is empty.
+    fn get_string_map(&self, name: &str) -> Result<HashMap<String, String>> {
+        let node = match self.get_property_opt::<MpvNode>(name)? {
+            Some(MpvNode::Map(map)) => map,
+            Some(_) => return Err(Error::Raw(mpv_error::PropertyFormat)),
+            None => return Ok(HashMap::new()),
+        };
+        Ok(node
+            .into_iter()
+            .filter_map(|(k, v)| match v {
+                MpvNode::String(v) => Some((k, v)),
+                _ => None,
+            })
+            .collect())
     }
 
     #[inline]
@@ -468,6 +2350,80 @@ impl Mpv {
         self.set_property("pause", false)
     }
 
+    #[inline]
+    /// Start playback, this is the same as `unpause`.
+    pub fn play(&self) -> Result<()> {
+        self.unpause()
+    }
+
+    #[inline]
+    /// Pause or unpause playback, depending on its current state.
+    ///
+    /// Unlike reading and setting `pause`, this can't race with other clients changing it.
+    pub fn toggle_pause(&self) -> Result<()> {
+        self.command("cycle", &["pause"])
+    }
+
+    #[inline]
+    /// Stop playback, and clear the playlist unless `keep_playlist` is set.
+    pub fn stop(&self, keep_playlist: bool) -> Result<()> {
+        if keep_playlist {
+            self.command("stop", &["keep-playlist"])
+        } else {
+            self.command("stop", &[])
+        }
+    }
+
+    #[inline]
+    /// Shut down the core, with `code` as exit code of the player if given. This sends
+    /// `Event::Shutdown` to every client, after which an event loop should stop.
+    ///
+    /// Once the core is shutting down, functions of this `Mpv` return errors, but it still has to
+    /// be dropped.
+    pub fn quit(&self, code: Option<i64>) -> Result<()> {
+        match code {
+            Some(code) => self.command("quit", &[&code.to_string()]),
+            None => self.command("quit", &[]),
+        }
+    }
+
+    #[inline]
+    /// The playback speed, `1.0` being normal speed.
+    pub fn speed(&self) -> Result<f64> {
+        self.get_property("speed")
+    }
+
+    #[inline]
+    /// Set the playback speed, `1.0` being normal speed.
+    ///
+    /// Returns `Error::InvalidArgument` if `rate` isn't positive or not finite.
+    pub fn set_speed(&self, rate: f64) -> Result<()> {
+        if !rate.is_finite() || rate <= 0. {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_property("speed", rate)
+    }
+
+    #[inline]
+    /// Set the playback speed back to normal.
+    pub fn reset_speed(&self) -> Result<()> {
+        self.set_speed(1.)
+    }
+
+    #[inline]
+    /// Add `delta` to the playback speed, which mpv clamps to its accepted range.
+    ///
+    /// This is done by mpv itself, so it can't race with other clients changing the speed.
+    ///
+    /// Returns `Error::InvalidArgument` if `delta` isn't finite.
+    pub fn adjust_speed(&self, delta: f64) -> Result<()> {
+        if !delta.is_finite() {
+            return Err(Error::InvalidArgument);
+        }
+        self.command_ret(&["add", "speed", &delta.to_string()])
+            .map(|_| ())
+    }
+
     // --- Convenience command functions ---
     //
 
@@ -529,6 +2485,132 @@ impl Mpv {
         Ok(())
     }
 
+    #[inline]
+    /// Expand mpv's path prefixes in `path`, e.g. `~~home/` or `~~desktop/`.
+    ///
+    /// Paths without such a prefix are returned unchanged.
+    pub fn expand_path(&self, path: &str) -> Result<String> {
+        match self.command_ret(&["expand-path", path])? {
+            MpvNode::String(path) => Ok(path),
+            _ => Err(Error::Raw(mpv_error::PropertyFormat)),
+        }
+    }
+
+    // --- Profile functions ---
+    //
+
+    #[inline]
+    /// The names of all profiles, including the builtin ones.
+    pub fn profile_list(&self) -> Result<Vec<String>> {
+        let list: MpvNode = self.get_property("profile-list")?;
+        list.as_array()
+            .ok_or(Error::Raw(mpv_error::PropertyFormat))?
+            .iter()
+            .map(|profile| {
+                profile
+                    .as_map()
+                    .and_then(|profile| profile.get("name"))
+                    .and_then(MpvNode::as_str)
+                    .map(str::to_owned)
+                    .ok_or(Error::Raw(mpv_error::PropertyFormat))
+            })
+            .collect()
+    }
+
+    #[inline]
+    /// Apply the options of the profile `name`.
+    pub fn apply_profile(&self, name: &str) -> Result<()> {
+        self.command("apply-profile", &[&quote_arg(name)])
+    }
+
+    #[inline]
+    /// Reset the option `name` to its default value, as reported by
+    /// `option-info/<name>/default-value`.
+    ///
+    /// This works for any option, while the `change-list` command only changes list options.
+    pub fn reset_option(&self, name: &str) -> Result<()> {
+        let default: String = self.get_property(&format!("option-info/{}/default-value", name))?;
+        self.set_property(name, default)
+    }
+
+    // --- Audio functions ---
+    //
+
+    #[inline]
+    /// Change the audio filter chain with `op`, like `set_video_filters`.
+    pub fn set_audio_filters(&self, op: FilterOp, spec: &str) -> Result<()> {
+        self.command_ret(&["af", op.val(), spec]).map(|_| ())
+    }
+
+    #[inline]
+    /// List the available audio output devices, including the `auto` device.
+    pub fn audio_devices(&self) -> Result<Vec<AudioDevice>> {
+        let list: MpvNode = self.get_property("audio-device-list")?;
+        let list = list
+            .as_array()
+            .ok_or(Error::Raw(mpv_error::PropertyFormat))?;
+
+        let mut devices = Vec::with_capacity(list.len());
+        for device in list {
+            let device = device
+                .as_map()
+                .ok_or(Error::Raw(mpv_error::PropertyFormat))?;
+            let field = |key| device.get(key).and_then(MpvNode::as_str);
+            devices.push(AudioDevice {
+                name: field("name")
+                    .ok_or(Error::Raw(mpv_error::PropertyFormat))?
+                    .to_owned(),
+                description: field("description").unwrap_or("").to_owned(),
+            });
+        }
+        Ok(devices)
+    }
+
+    #[inline]
+    /// The name of the current audio output device, `auto` unless set otherwise.
+    pub fn audio_device(&self) -> Result<String> {
+        self.get_property("audio-device")
+    }
+
+    #[inline]
+    /// Set the audio output device, `name` being one of `AudioDevice::name`.
+    pub fn set_audio_device(&self, name: &str) -> Result<()> {
+        self.set_property("audio-device", name)
+    }
+
+    #[inline]
+    /// Capture the decoded audio as float samples, e.g. for visualizations.
+    ///
+    /// # Side effects
+    /// This switches to the `pcm` audio output at runtime, which writes the audio to a temporary
+    /// file instead of playing it, so the audio is muted for the user. It also sets the
+    /// `audio-format` to `float`. Neither is restored when the `AudioCapture` is dropped, set `ao`
+    /// back to e.g. `auto` to play the audio again.
+    pub fn enable_audio_capture(&self) -> Result<AudioCapture> {
+        static NEXT_CAPTURE_ID: AtomicU64 = AtomicU64::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "mpv-rs-audio-{}-{}.pcm",
+            process::id(),
+            NEXT_CAPTURE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        File::create(&path)?;
+        let capture = AudioCapture {
+            file: OpenOptions::new().read(true).open(&path)?,
+            path,
+            partial: Vec::with_capacity(4),
+        };
+
+        let path = capture.path.to_str().ok_or(Error::InvalidUtf8)?;
+        self.set_property("ao-pcm-file", path)?;
+        self.set_property("ao-pcm-waveheader", false)?;
+        // Don't truncate the file when the audio output is reinitialized.
+        self.set_property("ao-pcm-append", true)?;
+        self.set_property("audio-format", "float")?;
+        self.set_property("ao", "pcm")?;
+        Ok(capture)
+    }
+
     // --- Seek functions ---
     //
 
@@ -580,17 +2662,114 @@ impl Mpv {
     }
 
     #[inline]
-    /// Seek exactly one frame, and pause.
-    /// Noop on audio only streams.
-    pub fn seek_frame(&self) -> Result<()> {
-        self.command("frame-step", &[])
-    }
+    /// Seek exactly one frame, and pause.
+    /// Noop on audio only streams.
+    pub fn seek_frame(&self) -> Result<()> {
+        self.command("frame-step", &[])
+    }
+
+    #[inline]
+    /// See `seek_frame`.
+    /// [Note performance considerations.](https://mpv.io/manual/master/#command-interface-frame-back-step)
+    pub fn seek_frame_backward(&self) -> Result<()> {
+        self.command("frame-back-step", &[])
+    }
+
+    #[inline]
+    /// Step one frame forward, using the `frame-step` command.
+    ///
+    /// This pauses playback as a side effect, and is a noop on audio only streams.
+    pub fn frame_step(&self) -> Result<()> {
+        self.seek_frame()
+    }
+
+    #[inline]
+    /// Step one frame backward, using the `frame-back-step` command.
+    ///
+    /// Like `frame_step`, this pauses playback as a side effect.
+    /// [Note performance considerations.](https://mpv.io/manual/master/#command-interface-frame-back-step)
+    pub fn frame_back_step(&self) -> Result<()> {
+        self.seek_frame_backward()
+    }
+
+    #[inline]
+    /// The number of the current video frame, estimated from the playback position and the
+    /// frame rate.
+    pub fn current_frame(&self) -> Result<i64> {
+        self.get_property("estimated-frame-number")
+    }
+
+    #[inline]
+    /// The number of video frames in the current file, estimated from the duration and the frame
+    /// rate. This is `None` if it's unknown, e.g. for variable frame rate content.
+    pub fn total_frames(&self) -> Result<Option<i64>> {
+        self.get_property_opt("estimated-frame-count")
+    }
+
+    // --- A-B loop functions ---
+    //
+
+    #[inline]
+    /// Loop playback between `a` and `b`, both given in seconds.
+    ///
+    /// Returns `Error::InvalidArgument` if either point is negative or not finite, or `b` is not
+    /// after `a`.
+    pub fn ab_loop_set(&self, a: f64, b: f64) -> Result<()> {
+        if !a.is_finite() || !b.is_finite() || a < 0. || b <= a {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_property("ab-loop-a", a)?;
+        self.set_property("ab-loop-b", b)
+    }
+
+    #[inline]
+    /// Clear both points of the A-B loop.
+    pub fn ab_loop_clear(&self) -> Result<()> {
+        self.set_property("ab-loop-a", "no")?;
+        self.set_property("ab-loop-b", "no")
+    }
+
+    #[inline]
+    /// Set how often the A-B loop is run, `None` loops forever.
+    pub fn ab_loop_count<A: Into<Option<usize>>>(&self, count: A) -> Result<()> {
+        if let Some(count) = count.into() {
+            self.set_property("ab-loop-count", count as i64)
+        } else {
+            self.set_property("ab-loop-count", "inf")
+        }
+    }
+
+    #[inline]
+    /// Loop playback between the given points in seconds, or clear the A-B loop if `None`.
+    ///
+    /// Returns `Error::InvalidArgument` if the range is empty or starts before `0`.
+    pub fn set_ab_loop(&self, range: Option<(f64, f64)>) -> Result<()> {
+        if let Some((a, b)) = range {
+            self.ab_loop_set(a, b)
+        } else {
+            self.ab_loop_clear()
+        }
+    }
+
+    #[inline]
+    /// The current A-B loop in seconds, `None` if either point is unset.
+    pub fn ab_loop(&self) -> Result<Option<(f64, f64)>> {
+        let point = |name| -> Result<Option<f64>> {
+            let value = self.get_property::<String>(name)?;
+            if value == "no" {
+                Ok(None)
+            } else {
+                value
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| Error::Raw(mpv_error::PropertyFormat))
+            }
+        };
 
-    #[inline]
-    /// See `seek_frame`.
-    /// [Note performance considerations.](https://mpv.io/manual/master/#command-interface-frame-back-step)
-    pub fn seek_frame_backward(&self) -> Result<()> {
-        self.command("frame-back-step", &[])
+        Ok(match (point("ab-loop-a")?, point("ab-loop-b")?) {
+            (Some(a), Some(b)) => Some((a, b)),
+            _ => None,
+        })
     }
 
     // --- Screenshot functions ---
@@ -636,9 +2815,124 @@ impl Mpv {
         }
     }
 
+    #[inline]
+    /// Take a screenshot like `screenshot_subtitles`, or `screenshot_video` if `include_subtitles`
+    /// is false, but return it instead of saving it to a file.
+    pub fn screenshot_raw_rgba(&self, include_subtitles: bool) -> Result<RawScreenshot> {
+        let flag = if include_subtitles {
+            "subtitles"
+        } else {
+            "video"
+        };
+        RawScreenshot::from_node(&self.command_ret(&["screenshot-raw", flag])?)
+    }
+
+    #[inline]
+    /// Take the current frame like `screenshot_raw_rgba` without subtitles, scaled to `width` by
+    /// `height` pixels in a row-major RGBA8 buffer, e.g. to create a thumbnail after seeking.
+    ///
+    /// The frame is scaled by picking the nearest pixel. Returns `Error::InvalidArgument` if a
+    /// dimension is not positive, and `Error::Raw(mpv_error::NothingToPlay)` if there is no video.
+    pub fn grab_frame_rgba(&self, width: i32, height: i32) -> Result<Vec<u8>> {
+        if width <= 0 || height <= 0 {
+            return Err(Error::InvalidArgument);
+        }
+        if self.video_params()?.is_none() {
+            return Err(Error::Raw(mpv_error::NothingToPlay));
+        }
+
+        let frame = self.screenshot_raw_rgba(false)?;
+        let (src_width, src_height) = (frame.width as usize, frame.height as usize);
+        let (width, height) = (width as usize, height as usize);
+        let mut buf = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            let row = &frame.data[y * src_height / height * frame.stride as usize..];
+            for x in 0..width {
+                let px = x * src_width / width * 4;
+                buf.extend_from_slice(&row[px..px + 4]);
+            }
+        }
+        Ok(buf)
+    }
+
     // --- Playlist functions ---
     //
 
+    #[inline]
+    /// The index of the current item in the playlist, `None` if there is none.
+    pub fn playlist_pos(&self) -> Result<Option<i64>> {
+        Ok(self
+            .get_property_opt::<i64>("playlist-pos")?
+            .filter(|&pos| pos >= 0))
+    }
+
+    #[inline]
+    /// Play the item at `pos` of the playlist.
+    pub fn set_playlist_pos(&self, pos: i64) -> Result<()> {
+        self.set_property("playlist-pos", pos)
+    }
+
+    #[inline]
+    /// Reload the current file, e.g. because it changed on disk, and seek back to the current
+    /// position once it's loaded. This blocks until the file was loaded.
+    ///
+    /// Returns `Error::InvalidArgument` if no file is playing, and the error of libmpv if
+    /// reloading failed.
+    pub fn refresh(&self) -> Result<()> {
+        let pos = self.playlist_pos()?.ok_or(Error::InvalidArgument)?;
+        let time_pos = self.get_property_opt::<f64>("time-pos")?;
+
+        // Created first, so that `FileLoaded` can't be missed.
+        let client = SubClient::new(self)?;
+        // Writing the current position restarts its playback.
+        self.set_playlist_pos(pos)?;
+        loop {
+            let event = unsafe { &*mpv_sys::mpv_wait_event(client.0.as_ptr(), -1.) };
+            match event.event_id {
+                events::mpv_event_id::FileLoaded => break,
+                events::mpv_event_id::EndFile => {
+                    let end_file = event.data as *const mpv_sys::mpv_event_end_file;
+                    if EndFileReason::from_raw(unsafe { (*end_file).reason })
+                        == EndFileReason::Error
+                    {
+                        return mpv_err((), unsafe { (*end_file).error });
+                    }
+                }
+                events::mpv_event_id::Shutdown => return Err(Error::CommandFailed),
+                _ => {}
+            }
+        }
+
+        match time_pos {
+            Some(time_pos) => self.seek_absolute(time_pos),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    /// Play the next item of the current playlist.
+    /// If the current item is the last item, `force` terminates playback, otherwise this does
+    /// nothing.
+    pub fn playlist_next(&self, force: bool) -> Result<()> {
+        if force {
+            self.playlist_next_force()
+        } else {
+            self.playlist_next_weak()
+        }
+    }
+
+    #[inline]
+    /// Play the previous item of the current playlist.
+    /// If the current item is the first item, `force` terminates playback, otherwise this does
+    /// nothing.
+    pub fn playlist_prev(&self, force: bool) -> Result<()> {
+        if force {
+            self.playlist_previous_force()
+        } else {
+            self.playlist_previous_weak()
+        }
+    }
+
     #[inline]
     /// Play the next item of the current playlist.
     /// Does nothing if the current item is the last item.
@@ -700,6 +2994,75 @@ impl Mpv {
         Ok(())
     }
 
+    #[inline]
+    /// Load the file at `path`, setting the given per-file `options` while it is played, e.g.
+    /// `("start", "30")`.
+    ///
+    /// Returns `Error::InvalidArgument` if an option name is empty, or contains `,` or `=`.
+    pub fn load_file(&self, path: &str, mode: FileState, options: &[(&str, &str)]) -> Result<()> {
+        let options = key_value_list(options)?;
+        self.command_ret(&["loadfile", path, mode.val(), &options])
+            .map(|_| ())
+    }
+
+    #[inline]
+    /// Reload the current file, but instead of playing it, copy its raw stream to the file at
+    /// `path` (`--stream-dump`). Afterwards, playback continues with the next playlist entry.
+    ///
+    /// The reloaded file is inserted into the playlist right after the current entry, which is
+    /// kept along with the rest of the playlist, and the option is only set for it.
+    /// Returns `Error::InvalidArgument` if no file is playing, and `Error::InvalidUtf8` if `path`
+    /// is not valid UTF-8.
+    pub fn stream_dump(&self, path: &Path) -> Result<()> {
+        let path = path.to_str().ok_or(Error::InvalidUtf8)?;
+        let source = self
+            .get_property_opt::<String>("path")?
+            .ok_or(Error::InvalidArgument)?;
+        let pos = self.playlist_pos()?.ok_or(Error::InvalidArgument)?;
+
+        // `replace` would clear the playlist, and `insert-next` needs a newer libmpv, so append
+        // the entry and move it after the current one instead.
+        self.load_file(&source, FileState::Append, &[("stream-dump", path)])?;
+        let last = self.get_property::<i64>("playlist-count")? - 1;
+        self.command(
+            "playlist-move",
+            &[&format!("{}", last), &format!("{}", pos + 1)],
+        )?;
+        self.set_playlist_pos(pos + 1)
+    }
+
+    #[inline]
+    /// Abort a dump started by `stream_dump`, by stopping the current file. The playlist is kept.
+    pub fn cancel_stream_dump(&self) -> Result<()> {
+        self.stop(true)
+    }
+
+    #[inline]
+    /// Start recording the current stream to the file at `path`, while playing it
+    /// (`record-file`). The container format is guessed from the extension of `path`.
+    ///
+    /// Returns `Error::InvalidArgument` if no file is playing, and `Error::InvalidUtf8` if `path`
+    /// is not valid UTF-8.
+    pub fn start_recording(&self, path: &Path) -> Result<()> {
+        let path = path.to_str().ok_or(Error::InvalidUtf8)?;
+        if self.get_property_opt::<String>("path")?.is_none() {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_property("record-file", path)
+    }
+
+    #[inline]
+    /// Stop the recording started by `start_recording`, and finish its file.
+    pub fn stop_recording(&self) -> Result<()> {
+        self.set_property("record-file", "")
+    }
+
+    #[inline]
+    /// Whether the current stream is recorded, see `start_recording`.
+    pub fn is_recording(&self) -> Result<bool> {
+        Ok(!self.get_property::<String>("record-file")?.is_empty())
+    }
+
     #[inline]
     /// Load the given playlist file, that either replaces the current playlist, or appends to it.
     pub fn playlist_load_list(&self, path: &str, replace: bool) -> Result<()> {
@@ -756,12 +3119,8 @@ impl Mpv {
         lang: B,
     ) -> Result<()> {
         match (title.into(), lang.into()) {
-            (None, None) => self.command("sub-add", &[&format!("\"{}\"", path), "select"]),
-            (Some(t), None) => self.command("sub-add", &[&format!("\"{}\"", path), "select", t]),
             (None, Some(_)) => panic!("Given subtitle language, but missing title"),
-            (Some(t), Some(l)) => {
-                self.command("sub-add", &[&format!("\"{}\"", path), "select", t, l])
-            }
+            (title, lang) => self.add_subtitle(path, SubtitleFlag::Select, title, lang),
         }
     }
 
@@ -780,12 +3139,8 @@ impl Mpv {
         lang: B,
     ) -> Result<()> {
         match (title.into(), lang.into()) {
-            (None, None) => self.command("sub-add", &[&format!("\"{}\"", path), "auto"]),
-            (Some(t), None) => self.command("sub-add", &[&format!("\"{}\"", path), "auto", t]),
-            (Some(t), Some(l)) => {
-                self.command("sub-add", &[&format!("\"{}\"", path), "auto", t, l])
-            }
             (None, Some(_)) => panic!("Given subtitle language, but missing title"),
+            (title, lang) => self.add_subtitle(path, SubtitleFlag::Auto, title, lang),
         }
     }
 
@@ -795,7 +3150,34 @@ impl Mpv {
     /// (In this case, title/language are ignored, and if the [sub] was changed since it was loaded,
     /// these changes won't be reflected.)".
     pub fn subtitle_add_cached(&self, path: &str) -> Result<()> {
-        self.command("sub-add", &[&format!("\"{}\"", path), "cached"])
+        self.add_subtitle(path, SubtitleFlag::Cached, None, None)
+    }
+
+    #[inline]
+    /// Add the external subtitle file `path`, with an optional title and language.
+    ///
+    /// A language can be given without a title, unlike with `subtitle_add_select`.
+    pub fn add_subtitle(
+        &self,
+        path: &str,
+        flag: SubtitleFlag,
+        title: Option<&str>,
+        lang: Option<&str>,
+    ) -> Result<()> {
+        let mut args = vec!["sub-add", path, flag.val()];
+        match (title, lang) {
+            (None, None) => {}
+            (Some(t), None) => args.push(t),
+            // mpv ignores an empty title.
+            (t, Some(l)) => args.extend(&[t.unwrap_or(""), l]),
+        }
+        self.command_ret(&args).map(|_| ())
+    }
+
+    #[inline]
+    /// Select the next subtitle track, or none after the last one.
+    pub fn cycle_subtitle(&self) -> Result<()> {
+        self.command("cycle", &["sub"])
     }
 
     #[inline]
@@ -841,4 +3223,413 @@ impl Mpv {
     pub fn subtitle_seek_backward(&self) -> Result<()> {
         self.command("sub-seek", &["-1"])
     }
+
+    #[inline]
+    /// Set the vertical position of subtitles in percent of the screen height, `0` being the top
+    /// and `100` the bottom. Values up to `150` are accepted.
+    ///
+    /// Returns `Error::InvalidArgument` if `percent` is above `150`.
+    pub fn set_sub_pos(&self, percent: u32) -> Result<()> {
+        if percent > 150 {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_property("sub-pos", i64::from(percent))
+    }
+
+    #[inline]
+    /// Set the vertical margin of subtitles in scaled pixels.
+    pub fn set_sub_margin_y(&self, px: i32) -> Result<()> {
+        self.set_property("sub-margin-y", i64::from(px))
+    }
+
+    #[inline]
+    /// Set the horizontal margin of subtitles in scaled pixels.
+    pub fn set_sub_margin_x(&self, px: i32) -> Result<()> {
+        self.set_property("sub-margin-x", i64::from(px))
+    }
+
+    #[inline]
+    /// Whether to place toptitles and subtitles in black borders when they are available
+    /// (`--sub-use-margins`).
+    pub fn set_sub_use_margins(&self, enable: bool) -> Result<()> {
+        self.set_property("sub-use-margins", enable)
+    }
+
+    #[inline]
+    /// Whether ASS subtitles are scaled and positioned using the aspect ratio and size of the
+    /// video (`--ass-use-video-data`), which enables all kinds of aspect ratio correction.
+    pub fn set_ass_use_video_data(&self, enable: bool) -> Result<()> {
+        self.set_property("ass-use-video-data", if enable { "all" } else { "none" })
+    }
+
+    // --- Video functions ---
+    //
+
+    #[inline]
+    /// Whether video is deinterlaced (`--deinterlace`).
+    pub fn deinterlace(&self) -> Result<bool> {
+        self.get_property("deinterlace")
+    }
+
+    #[inline]
+    /// Enable or disable deinterlacing of the video (`--deinterlace`).
+    pub fn set_deinterlace(&self, enabled: bool) -> Result<()> {
+        self.set_property("deinterlace", enabled)
+    }
+
+    #[inline]
+    /// Whether frames are interpolated to the refresh rate of the display (`--interpolation`).
+    pub fn interpolation(&self) -> Result<bool> {
+        self.get_property("interpolation")
+    }
+
+    #[inline]
+    /// Enable or disable interpolation of frames (`--interpolation`), which only has an effect
+    /// with one of the `display-*` video sync modes.
+    pub fn set_interpolation(&self, enabled: bool) -> Result<()> {
+        self.set_property("interpolation", enabled)
+    }
+
+    #[inline]
+    /// The current video sync mode (`--video-sync`), e.g. `audio`.
+    pub fn video_sync_mode(&self) -> Result<String> {
+        self.get_property_string("video-sync")
+    }
+
+    #[inline]
+    /// Change the video filter chain with `op`, e.g. `FilterOp::Add` and `lavfi-crop=640:480`.
+    /// `spec` uses the filter syntax of mpv, and is passed as is.
+    pub fn set_video_filters(&self, op: FilterOp, spec: &str) -> Result<()> {
+        self.command_ret(&["vf", op.val(), spec]).map(|_| ())
+    }
+
+    #[inline]
+    /// Set how audio and video are kept in sync (`--video-sync`).
+    pub fn set_video_sync_mode(&self, mode: VideoSyncMode) -> Result<()> {
+        self.set_property("video-sync", mode.val())
+    }
+
+    // --- Window functions ---
+    //
+
+    #[inline]
+    /// The parameters of the current video (`video-params`), `None` if there is none, e.g. for
+    /// audio files.
+    pub fn video_params(&self) -> Result<Option<VideoParams>> {
+        Ok(self
+            .get_property_opt::<MpvNode>("video-params")?
+            .and_then(|params| VideoParams::from_node(&params)))
+    }
+
+    #[inline]
+    /// The dimensions and aspect ratio of the current video.
+    pub fn video_geometry(&self) -> Result<VideoGeometry> {
+        Ok(VideoGeometry {
+            width: self.get_property("video-params/w")?,
+            height: self.get_property("video-params/h")?,
+            dwidth: self.get_property("dwidth")?,
+            dheight: self.get_property("dheight")?,
+            aspect: self.get_property("video-params/aspect")?,
+        })
+    }
+
+    #[inline]
+    /// Override the aspect ratio of the video, `0` meaning square pixels.
+    ///
+    /// Returns `Error::InvalidArgument` if `aspect` is negative or not finite, see
+    /// `reset_video_aspect` to remove the override.
+    pub fn set_video_aspect(&self, aspect: f64) -> Result<()> {
+        if !aspect.is_finite() || aspect < 0. {
+            return Err(Error::InvalidArgument);
+        }
+        self.set_property("video-aspect-override", aspect)
+    }
+
+    #[inline]
+    /// Use the aspect ratio of the video again, see `set_video_aspect`.
+    pub fn reset_video_aspect(&self) -> Result<()> {
+        self.set_property("video-aspect-override", -1.)
+    }
+
+    #[inline]
+    /// Set the initial window geometry, given in the X11 `[WxH][+X+Y]` format.
+    ///
+    /// Returns `Error::InvalidGeometry` if `geom` is malformed, without passing it to mpv.
+    pub fn set_geometry_string(&self, geom: &str) -> Result<()> {
+        geom.parse::<Geometry>()?;
+        self.set_property("geometry", geom)
+    }
+
+    #[inline]
+    /// The displays the window is on, which is empty if there's no window.
+    ///
+    /// mpv only reports the refresh rate and scale factor of the display the window is mostly
+    /// on, so these are the same for every entry.
+    pub fn displays(&self) -> Result<Vec<DisplayInfo>> {
+        let names = match self.get_property_opt::<MpvNode>("display-names")? {
+            Some(names) => names,
+            None => return Ok(Vec::new()),
+        };
+        let names = names
+            .as_array()
+            .ok_or(Error::Raw(mpv_error::PropertyFormat))?;
+        let fps = self.get_property_opt("display-fps")?;
+        let hidpi_scale = self.get_property_opt("display-hidpi-scale")?;
+
+        names
+            .iter()
+            .map(|name| {
+                Ok(DisplayInfo {
+                    name: name
+                        .as_str()
+                        .ok_or(Error::Raw(mpv_error::PropertyFormat))?
+                        .to_owned(),
+                    fps,
+                    hidpi_scale,
+                })
+            })
+            .collect()
+    }
+
+    #[inline]
+    /// Show the window on the display with index `index` when in fullscreen (`--fs-screen`).
+    pub fn set_fullscreen_display(&self, index: usize) -> Result<()> {
+        self.set_property("fs-screen", &*index.to_string())
+    }
+
+    #[inline]
+    /// Set the display affinity of the window (`--window-affinity`).
+    pub fn set_window_affinity(&self, affinity: WindowAffinity) -> Result<()> {
+        self.set_property("window-affinity", affinity.val())
+    }
+
+    #[inline]
+    /// Set the title of the window (`--title`), which is subject to
+    /// [property expansion](https://mpv.io/manual/master/#property-expansion).
+    pub fn set_window_title(&self, title: &str) -> Result<()> {
+        self.set_property("title", title)
+    }
+
+    #[inline]
+    /// Whether the window has decorations, i.e. a border and title bar (`--border`).
+    pub fn has_border(&self) -> Result<bool> {
+        self.get_property("border")
+    }
+
+    #[inline]
+    /// Show or hide the decorations of the window (`--border`).
+    pub fn set_border(&self, show: bool) -> Result<()> {
+        self.set_property("border", show)
+    }
+
+    #[inline]
+    /// Keep the window on top of other windows (`--ontop`).
+    pub fn set_ontop(&self, ontop: bool) -> Result<()> {
+        self.set_property("ontop", ontop)
+    }
+
+    #[inline]
+    /// Enter or leave fullscreen (`--fullscreen`).
+    pub fn set_fullscreen(&self, fullscreen: bool) -> Result<()> {
+        self.set_property("fullscreen", fullscreen)
+    }
+
+    // --- OSD functions ---
+    //
+
+    #[inline]
+    /// Show `text` on the OSD for `duration_ms` milliseconds.
+    /// `text` is subject to [property expansion](https://mpv.io/manual/master/#property-expansion).
+    ///
+    /// Returns `Error::InvalidArgument` if `duration_ms` is negative.
+    pub fn show_osd_message(&self, text: &str, duration_ms: i32) -> Result<()> {
+        if duration_ms < 0 {
+            return Err(Error::InvalidArgument);
+        }
+        self.command(
+            "show-text",
+            &[&quote_arg(text), &format!("{}", duration_ms)],
+        )
+    }
+
+    #[inline]
+    /// Enable or disable the OSD, by setting `osd-level` to `1` or `0` respectively.
+    pub fn set_osd_visibility(&self, visible: bool) -> Result<()> {
+        self.set_property("osd-level", if visible { 1 } else { 0 })
+    }
+
+    #[inline]
+    /// Show the overlay `id`, replacing a previous one with the same id. `data` are ASS events,
+    /// i.e. the part after `Dialogue: ` of `.ass` files, separated by newlines, and positioned
+    /// for a canvas of `res_x` by `res_y` (`0` for the default of 720 height).
+    pub fn osd_overlay(&self, id: i64, data: &str, res_x: i64, res_y: i64) -> Result<()> {
+        self.command_ret(&[
+            "osd-overlay",
+            &id.to_string(),
+            "ass-events",
+            data,
+            &res_x.to_string(),
+            &res_y.to_string(),
+        ])
+        .map(|_| ())
+    }
+
+    #[inline]
+    /// Remove the overlay `id`, which does nothing if it doesn't exist.
+    pub fn remove_osd_overlay(&self, id: i64) -> Result<()> {
+        self.command_ret(&["osd-overlay", &id.to_string(), "none", ""])
+            .map(|_| ())
+    }
+
+    #[inline]
+    /// Show the progress bar, the elapsed time and the total duration of the file on the OSD.
+    pub fn show_progress_bar(&self) -> Result<()> {
+        self.command("show-progress", &[])
+    }
+
+    // --- Clipboard functions ---
+    //
+
+    #[inline]
+    /// Get the text in the clipboard.
+    ///
+    /// Returns `Error::CommandFailed` if mpv has no clipboard support on this platform.
+    pub fn clipboard_get(&self) -> Result<String> {
+        match self.command_ret(&["clipboard-get"]) {
+            Ok(MpvNode::String(text)) => Ok(text),
+            Ok(_)
+            | Err(Error::Raw(mpv_error::Command))
+            | Err(Error::Raw(mpv_error::InvalidParameter))
+            | Err(Error::Raw(mpv_error::NotImplemented)) => Err(Error::CommandFailed),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[inline]
+    /// Set the text in the clipboard.
+    ///
+    /// Returns `Error::CommandFailed` if mpv has no clipboard support on this platform.
+    pub fn clipboard_set(&self, text: &str) -> Result<()> {
+        match self.command_ret(&["clipboard-set", text]) {
+            Ok(_) => Ok(()),
+            Err(Error::Raw(mpv_error::Command))
+            | Err(Error::Raw(mpv_error::InvalidParameter))
+            | Err(Error::Raw(mpv_error::NotImplemented)) => Err(Error::CommandFailed),
+            Err(e) => Err(e),
+        }
+    }
+
+    // --- Watch later functions ---
+    //
+
+    #[inline]
+    /// Save the playback position and options of the current file, so that playback resumes
+    /// there when it's loaded again.
+    pub fn save_watch_later(&self) -> Result<()> {
+        self.command("write-watch-later-config", &[])
+    }
+
+    #[inline]
+    /// Delete the saved playback position of the current file, see `save_watch_later`.
+    pub fn clear_watch_later(&self) -> Result<()> {
+        self.command("delete-watch-later-config", &[])
+    }
+
+    #[inline]
+    /// Save playback positions in the directory at `path` (`--watch-later-directory`).
+    ///
+    /// Returns `Error::InvalidUtf8` if `path` is not valid UTF-8.
+    pub fn set_watch_later_directory(&self, path: &Path) -> Result<()> {
+        let path = path.to_str().ok_or(Error::InvalidUtf8)?;
+        self.set_property("watch-later-directory", path)
+    }
+
+    // --- Script functions ---
+    //
+
+    #[inline]
+    /// Load and run the script at `path`, e.g. a Lua or JavaScript file (`load-script`).
+    ///
+    /// Returns `Error::InvalidUtf8` if `path` isn't valid UTF-8.
+    pub fn run_script(&self, path: &Path) -> Result<()> {
+        let path = path.to_str().ok_or(Error::InvalidUtf8)?;
+        self.command_ret(&["load-script", path]).map(|_| ())
+    }
+
+    #[inline]
+    /// Send the message `name` with `args` to all scripts and clients (`script-message`).
+    pub fn send_script_message(&self, name: &str, args: &[&str]) -> Result<()> {
+        let mut cmd = Vec::with_capacity(args.len() + 2);
+        cmd.push("script-message");
+        cmd.push(name);
+        cmd.extend_from_slice(args);
+        self.command_ret(&cmd).map(|_| ())
+    }
+
+    // --- Input functions ---
+    //
+
+    #[inline]
+    /// "Send a key event through mpv's input handler, triggering whatever behavior is configured
+    /// for that key." `key` uses the same names as input.conf, e.g. `"SPACE"` or `"Ctrl+a"`.
+    pub fn key_press(&self, key: &str) -> Result<()> {
+        self.command_ret(&["keypress", key]).map(|_| ())
+    }
+
+    #[inline]
+    /// Like `key_press`, but only press the key down, without releasing it.
+    pub fn key_down(&self, key: &str) -> Result<()> {
+        self.command_ret(&["keydown", key]).map(|_| ())
+    }
+
+    #[inline]
+    /// Release a key previously pressed with `key_down`.
+    pub fn key_up(&self, key: &str) -> Result<()> {
+        self.command_ret(&["keyup", key]).map(|_| ())
+    }
+
+    #[inline]
+    /// Send a mouse move event to the given window coordinates.
+    pub fn mouse_move(&self, x: i32, y: i32) -> Result<()> {
+        self.command_ret(&["mouse", &x.to_string(), &y.to_string()])
+            .map(|_| ())
+    }
+
+    #[inline]
+    /// Run `command` when `key` is pressed, taking precedence over the bindings of input.conf.
+    /// `key` uses the same names as input.conf, and `command` the same syntax.
+    ///
+    /// Binding an already bound key replaces its command. Key names aren't validated here, mpv
+    /// logs and ignores invalid ones.
+    ///
+    /// To handle a key in Rust, bind it to `script-message <name>`, which is then received as a
+    /// `ClientMessage` event.
+    pub fn bind_key(&self, key: &str, command: &str) -> Result<()> {
+        let mut sections = self.key_sections.lock();
+        let section = match sections.get(key) {
+            Some(section) => section.clone(),
+            None => format!(
+                "mpv-rs-key-{}",
+                NEXT_SECTION_ID.fetch_add(1, Ordering::Relaxed)
+            ),
+        };
+
+        let contents = format!("{} {}", key, command);
+        self.command_ret(&["define-section", &section, &contents, "force"])?;
+        self.command_ret(&["enable-section", &section])?;
+        sections.insert(key.to_owned(), section);
+        Ok(())
+    }
+
+    #[inline]
+    /// Remove a binding of `bind_key`, which restores the binding of input.conf, if any.
+    ///
+    /// Returns `Error::InvalidArgument` if `key` wasn't bound with `bind_key`.
+    pub fn unbind_key(&self, key: &str) -> Result<()> {
+        let mut sections = self.key_sections.lock();
+        let section = sections.get(key).ok_or(Error::InvalidArgument)?;
+        self.command_ret(&["disable-section", section])?;
+        sections.remove(key);
+        Ok(())
+    }
 }
@@ -21,8 +21,77 @@ pub mod simple;
 #[cfg(feature = "events_sync")]
 pub mod sync;
 
+use std::os::raw as ctype;
+
+// The layouts of `mpv_event_start_file` and `mpv_event_end_file` since client API 1.108, which
+// added the playlist entry ids. The pinned headers predate them, and before 1.108 `StartFile`
+// carried no data.
+#[repr(C)]
+struct StartFileV108 {
+    playlist_entry_id: i64,
+}
+
+#[repr(C)]
+struct EndFileV108 {
+    reason: ctype::c_int,
+    error: ctype::c_int,
+    playlist_entry_id: i64,
+}
+
+fn has_playlist_entry_id() -> bool {
+    unsafe { mpv_sys::mpv_client_api_version() >= (1 << 16 | 108) }
+}
+
+// The playlist entry id of `MPV_EVENT_START_FILE` data, 0 if libmpv is too old to report it.
+pub(crate) unsafe fn start_file_entry_id(data: *mut ctype::c_void) -> i64 {
+    if data.is_null() || !has_playlist_entry_id() {
+        0
+    } else {
+        (*(data as *const StartFileV108)).playlist_entry_id
+    }
+}
+
+// The playlist entry id of `MPV_EVENT_END_FILE` data, 0 if libmpv is too old to report it.
+pub(crate) unsafe fn end_file_entry_id(data: *mut ctype::c_void) -> i64 {
+    if data.is_null() || !has_playlist_entry_id() {
+        0
+    } else {
+        (*(data as *const EndFileV108)).playlist_entry_id
+    }
+}
+
 /// An `Event`'s ID.
 pub use mpv_sys::mpv_event_id as EventId;
+
+// The ids of all events that libmpv may send, except `Shutdown` and `QueueOverflow`, which are
+// always wanted. This includes the deprecated ones, which this crate can't represent.
+#[cfg(feature = "events_sync")]
+const DISABLED_EVENT_IDS: [EventId; 23] = [
+    mpv_event_id::LogMessage,
+    mpv_event_id::GetPropertyReply,
+    mpv_event_id::SetPropertyReply,
+    mpv_event_id::CommandReply,
+    mpv_event_id::StartFile,
+    mpv_event_id::EndFile,
+    mpv_event_id::FileLoaded,
+    mpv_sys::mpv_event_id_MPV_EVENT_TRACKS_CHANGED,
+    mpv_sys::mpv_event_id_MPV_EVENT_TRACK_SWITCHED,
+    mpv_event_id::Idle,
+    mpv_sys::mpv_event_id_MPV_EVENT_PAUSE,
+    mpv_sys::mpv_event_id_MPV_EVENT_UNPAUSE,
+    mpv_event_id::Tick,
+    mpv_sys::mpv_event_id_MPV_EVENT_SCRIPT_INPUT_DISPATCH,
+    mpv_event_id::ClientMessage,
+    mpv_event_id::VideoReconfig,
+    mpv_event_id::AudioReconfig,
+    mpv_sys::mpv_event_id_MPV_EVENT_METADATA_UPDATE,
+    mpv_event_id::Seek,
+    mpv_event_id::PlaybackRestart,
+    mpv_event_id::PropertyChange,
+    mpv_sys::mpv_event_id_MPV_EVENT_CHAPTER_CHANGE,
+    mpv_event_id::Hook,
+];
+
 pub mod mpv_event_id {
     #![allow(missing_docs)]
     pub use mpv_sys::mpv_event_id_MPV_EVENT_AUDIO_RECONFIG as AudioReconfig;
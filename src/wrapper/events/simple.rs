@@ -16,8 +16,11 @@
 // License along with this library; if not, write to the Free Software
 // Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA  02110-1301  USA
 
-use super::mpv_event_id;
-use crate::{wrapper::mpv_err, *};
+use super::{end_file_entry_id, mpv_event_id, start_file_entry_id};
+use crate::{
+    wrapper::{command_reply_result, mpv_err, FileLoadedInfo},
+    *,
+};
 
 use std::ffi::CString;
 use std::iter::Map;
@@ -40,19 +43,36 @@ impl<'a> PropertyData<'a> {
     fn from_raw(format: MpvFormat, ptr: *mut ctype::c_void) -> Result<PropertyData<'a>> {
         assert!(!ptr.is_null());
         match format {
-            mpv_format::Flag => Ok(PropertyData::Flag(unsafe { *(ptr as *mut bool) })),
-            mpv_format::String => {
+            MpvFormat::Flag => Ok(PropertyData::Flag(
+                unsafe { *(ptr as *mut ctype::c_int) } != 0,
+            )),
+            MpvFormat::String => {
                 let char_ptr = unsafe { *(ptr as *mut *mut ctype::c_char) };
                 Ok(PropertyData::Str(unsafe { mpv_cstr_to_str!(char_ptr) }?))
             }
-            mpv_format::OsdString => {
+            MpvFormat::OsdString => {
                 let char_ptr = unsafe { *(ptr as *mut *mut ctype::c_char) };
                 Ok(PropertyData::OsdStr(unsafe { mpv_cstr_to_str!(char_ptr) }?))
             }
-            mpv_format::Double => Ok(PropertyData::Double(unsafe { *(ptr as *mut f64) })),
-            mpv_format::Int64 => Ok(PropertyData::Int64(unsafe { *(ptr as *mut i64) })),
-            mpv_format::None => unreachable!(),
-            _ => unimplemented!(),
+            MpvFormat::Double => Ok(PropertyData::Double(unsafe { *(ptr as *mut f64) })),
+            MpvFormat::Int64 => Ok(PropertyData::Int64(unsafe { *(ptr as *mut i64) })),
+            MpvFormat::None
+            | MpvFormat::Node
+            | MpvFormat::NodeArray
+            | MpvFormat::NodeMap
+            | MpvFormat::ByteArray
+            | MpvFormat::Unknown(_) => Err(Error::Raw(mpv_error::UnknownFormat)),
+        }
+    }
+
+    // Set property `name` to this value, see `Mpv::set_properties`.
+    pub(crate) fn set_on(&self, mpv: &Mpv, name: &str) -> Result<()> {
+        match *self {
+            PropertyData::Str(v) => mpv.set_property(name, v),
+            PropertyData::OsdStr(_) => Err(Error::InvalidArgument),
+            PropertyData::Flag(v) => mpv.set_property(name, v),
+            PropertyData::Int64(v) => mpv.set_property(name, v),
+            PropertyData::Double(v) => mpv.set_property(name, v),
         }
     }
 }
@@ -91,7 +111,8 @@ impl<'a> PartialEq for MessageIter<'a> {
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum Event<'a> {
-    /// Received when the player is shutting down
+    /// Received when the player is shutting down, e.g. because of `Mpv::quit` or the window was
+    /// closed. Afterwards, most functions return errors.
     Shutdown,
     /// *Has not been tested*, received when explicitly asked to MPV
     LogMessage {
@@ -108,20 +129,38 @@ pub enum Event<'a> {
     },
     /// Received when using set_property_async
     SetPropertyReply(u64),
-    /// Received when using command_async
-    CommandReply(u64),
-    /// Event received when a new file is playing
-    StartFile,
-    /// Event received when the file being played currently has stopped, for an error or not
-    EndFile(EndFileReason),
-    /// Event received when a file has been *loaded*, but has not been started
-    FileLoaded,
+    /// Received when using `Mpv::command_node_async`, with the result of the command.
+    CommandReply {
+        reply_userdata: u64,
+        result: MpvNode,
+    },
+    /// Event received when a new file is playing.
+    /// `playlist_entry_id` is 0 if libmpv is too old to report it.
+    StartFile {
+        playlist_entry_id: i64,
+    },
+    /// Event received when the file being played currently has stopped, for an error or not.
+    /// `playlist_entry_id` is 0 if libmpv is too old to report it.
+    EndFile {
+        reason: EndFileReason,
+        playlist_entry_id: i64,
+    },
+    /// Event received when a file has been *loaded*, but has not been started. The data is read
+    /// right after, `duration` is `None` e.g. for live streams.
+    FileLoaded {
+        tracks: Vec<Track>,
+        duration: Option<f64>,
+        filename: String,
+    },
     /// Received when the player has no more files to play and is in an idle state
     Idle,
     Unpause,
     Tick,
     ClientMessage(MessageIter<'a>),
-    VideoReconfig,
+    /// `params` is `None` if the video was disabled.
+    VideoReconfig {
+        params: Option<VideoParams>,
+    },
     AudioReconfig,
     /// The player changed current position
     Seek,
@@ -167,7 +206,7 @@ impl Mpv {
                         prefix,
                         level: mpv_cstr_to_str!(log_message.level)?,
                         text: mpv_cstr_to_str!(log_message.text)?,
-                        log_level: log_message.log_level,
+                        log_level: LogLevel::from_raw(log_message.log_level),
                     })
                 }))
             }
@@ -176,7 +215,10 @@ impl Mpv {
                 Some(mpv_cstr_to_str!(property.name).and_then(|name| {
                     Ok(Event::GetPropertyReply {
                         name,
-                        result: PropertyData::from_raw(property.format, property.data)?,
+                        result: PropertyData::from_raw(
+                            MpvFormat::from_raw(property.format),
+                            property.data,
+                        )?,
                         reply_userdata: event.reply_userdata,
                     })
                 }))
@@ -185,23 +227,41 @@ impl Mpv {
                 Event::SetPropertyReply(event.reply_userdata),
                 event.error,
             )),
-            mpv_event_id::CommandReply => Some(mpv_err(
-                Event::CommandReply(event.reply_userdata),
-                event.error,
-            )),
-            mpv_event_id::StartFile => Some(Ok(Event::StartFile)),
+            mpv_event_id::CommandReply => {
+                Some(
+                    command_reply_result(event).map(|result| Event::CommandReply {
+                        reply_userdata: event.reply_userdata,
+                        result,
+                    }),
+                )
+            }
+            mpv_event_id::StartFile => Some(Ok(Event::StartFile {
+                playlist_entry_id: start_file_entry_id(event.data),
+            })),
             mpv_event_id::EndFile => {
                 let end_file = *(event.data as *mut mpv_sys::mpv_event_end_file);
 
                 if let Err(e) = mpv_err((), end_file.error) {
                     Some(Err(e))
-                } else if end_file.reason.is_positive() {
-                    Some(Ok(Event::EndFile(end_file.reason as _)))
                 } else {
-                    None
+                    Some(Ok(Event::EndFile {
+                        reason: EndFileReason::from_raw(end_file.reason),
+                        playlist_entry_id: end_file_entry_id(event.data),
+                    }))
                 }
             }
-            mpv_event_id::FileLoaded => Some(Ok(Event::FileLoaded)),
+            mpv_event_id::FileLoaded => {
+                let FileLoadedInfo {
+                    tracks,
+                    duration,
+                    filename,
+                } = FileLoadedInfo::from_ctx(self.ctx);
+                Some(Ok(Event::FileLoaded {
+                    tracks,
+                    duration,
+                    filename,
+                }))
+            }
             mpv_event_id::Idle => Some(Ok(Event::Idle)),
             mpv_event_id::Tick => Some(Ok(Event::Tick)),
             mpv_event_id::ClientMessage => {
@@ -213,7 +273,9 @@ impl Mpv {
                     client_message.num_args as _,
                 ))))
             }
-            mpv_event_id::VideoReconfig => Some(Ok(Event::VideoReconfig)),
+            mpv_event_id::VideoReconfig => Some(Ok(Event::VideoReconfig {
+                params: VideoParams::from_ctx(self.ctx),
+            })),
             mpv_event_id::AudioReconfig => Some(Ok(Event::AudioReconfig)),
             mpv_event_id::Seek => Some(Ok(Event::Seek)),
             mpv_event_id::PlaybackRestart => Some(Ok(Event::PlaybackRestart)),
@@ -222,13 +284,16 @@ impl Mpv {
 
                 // This happens if the property is not available. For example,
                 // if you reached EndFile while observing a property.
-                if property.format == mpv_format::None {
+                if MpvFormat::from_raw(property.format) == MpvFormat::None {
                     None
                 } else {
                     Some(mpv_cstr_to_str!(property.name).and_then(|name| {
                         Ok(Event::PropertyChange {
                             name,
-                            change: PropertyData::from_raw(property.format, property.data)?,
+                            change: PropertyData::from_raw(
+                                MpvFormat::from_raw(property.format),
+                                property.data,
+                            )?,
                             reply_userdata: event.reply_userdata,
                         })
                     }))
@@ -248,7 +313,7 @@ impl Mpv {
                 self.ctx.as_ptr(),
                 id,
                 name.as_ptr(),
-                format.as_mpv_format() as _,
+                format.as_mpv_format().as_raw(),
             )
         })
     }
@@ -18,14 +18,36 @@
 
 use parking_lot::{Condvar, Mutex};
 
-use crate::{events::*, wrapper::mpv_err, *};
+use super::{end_file_entry_id, start_file_entry_id, DISABLED_EVENT_IDS};
+use crate::{
+    events::*,
+    wrapper::{command_reply_result, mpv_err, wait_timeout, FileLoadedInfo, SubClient},
+    *,
+};
 
 use std::{
-    collections::HashMap, ffi::CString, marker::PhantomData, os::raw as ctype, ptr::NonNull,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ffi::CString,
+    fmt,
+    marker::PhantomData,
+    mem,
+    os::raw as ctype,
+    ptr::NonNull,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    sync::Arc,
+    time::{Duration, Instant},
+    vec,
 };
 
-unsafe extern "C" fn event_callback(d: *mut ctype::c_void) {
-    (*(d as *mut Condvar)).notify_one();
+/// The `EventDriver` that wakes up waiting `EventIter`s, which is always installed.
+pub(crate) struct DefaultEventDriver(Arc<(Mutex<bool>, Condvar)>);
+
+impl EventDriver for DefaultEventDriver {
+    #[inline]
+    fn wake(&self) {
+        self.0 .1.notify_one();
+    }
 }
 
 impl Mpv {
@@ -35,141 +57,347 @@ impl Mpv {
     ///
     /// This disables all events.
     pub fn new() -> Result<Mpv> {
-        let api_version = unsafe { mpv_sys::mpv_client_api_version() };
-        if crate::MPV_CLIENT_API_VERSION != api_version {
-            return Err(Error::VersionMismatch {
-                linked: crate::MPV_CLIENT_API_VERSION,
-                loaded: api_version,
-            });
-        }
-
-        let ctx = unsafe { mpv_sys::mpv_create() };
-        if ctx.is_null() {
-            return Err(Error::Null);
-        }
-
-        let (ev_iter_notification, ev_to_observe, ev_to_observe_properties, ev_observed) = {
-            let ev_iter_notification = Box::new((Mutex::new(false), Condvar::new()));
-            unsafe {
-                mpv_sys::mpv_set_wakeup_callback(
-                    ctx,
-                    Some(event_callback),
-                    &ev_iter_notification.1 as *const Condvar as *mut Condvar as *mut _,
-                );
-            }
+        MpvBuilder::new()?.build()
+    }
 
-            (
-                ev_iter_notification,
-                Mutex::new(Vec::with_capacity(10)),
-                Mutex::new(HashMap::with_capacity(10)),
-                Mutex::new(Vec::with_capacity(15)),
-            )
+    /// Take ownership of `ctx`, which is destroyed when the `Mpv` is dropped, terminating the
+    /// core if `terminate` is set.
+    ///
+    /// This disables all events of `ctx`.
+    pub(crate) fn from_ctx(ctx: NonNull<mpv_sys::mpv_handle>, terminate: bool) -> Result<Mpv> {
+        // Constructed first, so that `ctx` is destroyed on errors.
+        let mpv = Mpv {
+            ctx,
+            ev_iter_notification: Arc::new((Mutex::new(false), Condvar::new())),
+            ev_to_observe: Mutex::new(Vec::with_capacity(10)),
+            ev_to_observe_properties: Mutex::new(HashMap::with_capacity(10)),
+            ev_observed: Mutex::new(Vec::with_capacity(15)),
+            ev_shutdown: AtomicBool::new(false),
+            #[cfg(feature = "protocols")]
+            protocols_guard: AtomicBool::new(false),
+            wakeup_callback: Mutex::new(None),
+            log_level: AtomicU32::new(mpv_log_level::None),
+            key_sections: Mutex::new(HashMap::new()),
+            terminate,
         };
+        let driver = mpv.default_event_driver();
+        mpv.set_raw_wakeup_callback(Box::new(move || driver.wake()));
 
-        for i in 2..24 {
-            if let Err(e) = mpv_err((), unsafe { mpv_sys::mpv_request_event(ctx, i, 0) }) {
-                unsafe { mpv_sys::mpv_terminate_destroy(ctx) };
-                return Err(e);
-            }
+        for &id in &DISABLED_EVENT_IDS {
+            mpv_err((), unsafe {
+                mpv_sys::mpv_request_event(ctx.as_ptr(), id, 0)
+            })?;
         }
+        Ok(mpv)
+    }
 
-        mpv_err((), unsafe { mpv_sys::mpv_initialize(ctx) }).or_else(|err| {
-            unsafe { mpv_sys::mpv_terminate_destroy(ctx) };
-            Err(err)
-        })?;
+    #[inline]
+    /// Set a callback that is called whenever there are new events, e.g. to wake up a
+    /// platform-specific event loop.
+    ///
+    /// `EventIter`s are still notified as well.
+    /// The callback may be called from any thread, and must not call any mpv API itself.
+    pub fn set_wakeup_callback<F: Fn() + Send + 'static>(&self, f: F) {
+        let default = self.default_event_driver();
+        self.set_raw_wakeup_callback(Box::new(move || {
+            default.wake();
+            f();
+        }));
+    }
 
-        Ok(Mpv {
-            ctx: unsafe { NonNull::new_unchecked(ctx) },
-            ev_iter_notification,
-            ev_to_observe,
-            ev_to_observe_properties,
-            ev_observed,
-            #[cfg(feature = "protocols")]
-            protocols_guard: AtomicBool::new(false),
-        })
+    #[inline]
+    /// Let `driver` wake up the event loop of the application whenever there are new events,
+    /// e.g. of winit or gtk. This replaces the wakeup callback.
+    ///
+    /// `EventIter`s are still notified as well.
+    pub fn set_event_driver<D: EventDriver>(&self, driver: D) {
+        let default = self.default_event_driver();
+        self.set_raw_wakeup_callback(Box::new(move || {
+            default.wake();
+            driver.wake();
+        }));
+    }
+
+    fn default_event_driver(&self) -> DefaultEventDriver {
+        DefaultEventDriver(self.ev_iter_notification.clone())
     }
 
     #[inline]
     /// Observe given `Event`s via an `EventIter`.
     ///
+    /// Returns `Error::Raw(mpv_error::PropertyFormat)` if the `PropertyData` of an observed
+    /// property has a type that the property can't be converted to, e.g. `Flag` for `volume`.
+    ///
     /// # Panics
     /// If an event is set to be observed that has been previously set to be observed.
     pub fn observe_events(&self, events: &[Event]) -> Result<EventIter> {
+        // mpv would only send unavailable values for these, so catch the mistake early. This
+        // can't detect mismatches of properties that are currently unavailable.
+        for ev in events {
+            if let Event::PropertyChange { ref name, ref data } = *ev {
+                let ret = match *data {
+                    PropertyData::String(_) => self.get_property::<String>(name).map(|_| ()),
+                    PropertyData::OsdString(_) => self.get_property_osd(name).map(|_| ()),
+                    PropertyData::Flag(_) => self.get_property::<bool>(name).map(|_| ()),
+                    PropertyData::Int64(_) => self.get_property::<i64>(name).map(|_| ()),
+                    PropertyData::Double(_) => self.get_property::<f64>(name).map(|_| ()),
+                    PropertyData::None => Ok(()),
+                };
+                if let Err(Error::Raw(mpv_error::PropertyFormat)) = ret {
+                    return Err(Error::Raw(mpv_error::PropertyFormat));
+                }
+            }
+        }
+
         let mut observe = self.ev_to_observe.lock();
         let mut properties = self.ev_to_observe_properties.lock();
 
         let len = events.len();
-        let mut ids = Vec::with_capacity(len);
         let mut evs = Vec::with_capacity(len);
         let mut props = Vec::with_capacity(len);
         for elem in events {
             if let Event::PropertyChange { ref name, ref data } = *elem {
                 if properties.contains_key(name) {
                     panic!("Tried to observe {} twice", name);
-                } else {
-                    mpv_err((), unsafe {
-                        mpv_sys::mpv_request_event(self.ctx.as_ptr(), elem.as_id(), 1)
-                    })?;
-                    props.push((name, data));
-                    ids.push(elem.as_id());
-                    evs.push(elem.clone());
                 }
+                props.push((name, CString::new(&name[..])?, data.format()));
             } else {
                 for id in &*observe {
                     if elem.as_id() == id.as_id() {
                         panic!("Tried to observe {:?} twice", elem);
                     }
                 }
+            }
+            evs.push(elem.clone());
+        }
 
+        // Everything enabled below is disabled again if a later step fails, so that a failed
+        // call doesn't change what is observed.
+        let prev_log_level = self.log_level.load(Ordering::SeqCst);
+        let mut enabled = Vec::with_capacity(len);
+        let mut props_ins = Vec::with_capacity(props.len());
+        let start_id = properties.len();
+        let ret = (|| {
+            for elem in &evs {
                 if let Event::LogMessage { level: lvl, .. } = *elem {
-                    let min_level = CString::new(mpv_log_level_as_str(lvl))?;
+                    let min_level = CString::new(lvl.to_string())?;
                     mpv_err((), unsafe {
                         mpv_sys::mpv_request_log_messages(self.ctx.as_ptr(), min_level.as_ptr())
                     })?;
+                    self.log_level.store(lvl.as_raw(), Ordering::SeqCst);
                 }
 
                 mpv_err((), unsafe {
                     mpv_sys::mpv_request_event(self.ctx.as_ptr(), elem.as_id(), 1)
                 })?;
-                ids.push(elem.as_id());
-                evs.push(elem.clone());
+                enabled.push(elem.as_id());
             }
-        }
 
-        let mut props_ins = Vec::with_capacity(len);
-        let start_id = properties.len();
-        for (i, elem) in props.iter().enumerate() {
-            let name = CString::new(&elem.0[..])?;
-            let err = mpv_err((), unsafe {
-                mpv_sys::mpv_observe_property(
-                    self.ctx.as_ptr(),
-                    (start_id + i) as _,
-                    name.as_ptr(),
-                    elem.1.format() as _,
-                )
-            });
-            if err.is_err() {
-                // Ignore errors.
-                for (_, id) in props_ins {
-                    unsafe { mpv_sys::mpv_unobserve_property(self.ctx.as_ptr(), id) };
+            for (i, (name, raw_name, format)) in props.iter().enumerate() {
+                mpv_err((), unsafe {
+                    mpv_sys::mpv_observe_property(
+                        self.ctx.as_ptr(),
+                        (start_id + i) as _,
+                        raw_name.as_ptr(),
+                        format.as_raw(),
+                    )
+                })?;
+                props_ins.push(((*name).clone(), (start_id + i) as _));
+            }
+            Ok(())
+        })();
+
+        if let Err(e) = ret {
+            // Ignore errors.
+            for (_, id) in props_ins {
+                unsafe { mpv_sys::mpv_unobserve_property(self.ctx.as_ptr(), id) };
+            }
+            for id in enabled {
+                // Other `EventIter`s may still observe properties.
+                if id != mpv_event_id::PropertyChange || properties.is_empty() {
+                    unsafe { mpv_sys::mpv_request_event(self.ctx.as_ptr(), id, 0) };
                 }
-                return Err(err.unwrap_err());
             }
-            props_ins.push((elem.0.clone(), (start_id + i) as _));
+            if self.log_level.swap(prev_log_level, Ordering::SeqCst) != prev_log_level {
+                let min_level =
+                    CString::new(LogLevel::from_raw(prev_log_level).to_string()).unwrap();
+                unsafe { mpv_sys::mpv_request_log_messages(self.ctx.as_ptr(), min_level.as_ptr()) };
+            }
+            return Err(e);
         }
         observe.extend(evs.clone());
         properties.extend(props_ins);
 
-        Ok(EventIter {
+        Ok(self.event_iter(evs))
+    }
+
+    #[inline]
+    /// Iterate over the `Event`s observed by `token` again, see `EventIter::into_token`.
+    ///
+    /// # Panics
+    /// If `token` was created by another `Mpv` instance.
+    pub fn resume_events(&self, token: ObserveToken) -> EventIter<'_> {
+        assert_eq!(
+            self.ctx, token.ctx,
+            "ObserveToken used with another Mpv instance"
+        );
+        self.event_iter(token.events)
+    }
+
+    #[inline]
+    /// Stop observing the `Event`s of `token`, like dropping the `EventIter` it was created from.
+    ///
+    /// # Panics
+    /// If `token` was created by another `Mpv` instance.
+    pub fn unobserve(&self, token: ObserveToken) {
+        drop(self.resume_events(token));
+    }
+
+    #[inline]
+    /// Observe the property `name` like `observe_events`, but yield its current value as the
+    /// first `Event::PropertyChange`, unless it's unavailable.
+    ///
+    /// libmpv also reports the current value once the property is observed, so the initial value
+    /// is usually yielded twice. Skip consecutive equal values if that matters, like
+    /// `seeking_events` does.
+    ///
+    /// Returns `Error::InvalidArgument` for `Format::Node`, which `PropertyData` can't hold.
+    ///
+    /// # Panics
+    /// If the property has been previously set to be observed.
+    pub fn observe_property_with_initial(
+        &self,
+        name: &str,
+        format: Format,
+    ) -> Result<EventIter<'_>> {
+        let mut iter = self.observe_events(&[Event::PropertyChange {
+            name: name.to_owned(),
+            data: PropertyData::placeholder(format)?,
+        }])?;
+
+        let data = match format {
+            Format::String => self.get_property(name).map(PropertyData::String),
+            Format::Flag => self.get_property(name).map(PropertyData::Flag),
+            Format::Int64 => self.get_property(name).map(PropertyData::Int64),
+            Format::Double => self.get_property(name).map(PropertyData::Double),
+            Format::Node => unreachable!(),
+        };
+        match data {
+            Ok(data) => iter.initial.get_mut().push(Event::PropertyChange {
+                name: name.to_owned(),
+                data,
+            }),
+            Err(Error::Raw(mpv_error::PropertyUnavailable)) => {}
+            Err(e) => return Err(e),
+        }
+        Ok(iter)
+    }
+
+    #[inline]
+    /// Watch the property `name` through a `PropertyWatcher`, which only keeps its latest
+    /// change, e.g. for `time-pos` which changes many times per second.
+    ///
+    /// # Panics
+    /// If the property has been previously set to be observed.
+    pub fn watch_property<T: PropertyType + Default>(
+        &self,
+        name: &str,
+    ) -> Result<PropertyWatcher<'_, T>> {
+        let iter = self.observe_events(&[Event::PropertyChange {
+            name: name.to_owned(),
+            data: T::default().into_property_data(),
+        }])?;
+        Ok(PropertyWatcher {
+            mpv: self,
+            name: name.to_owned(),
+            iter,
+            _ty: PhantomData,
+        })
+    }
+
+    #[inline]
+    /// Yield whether a seek is in progress (`seeking`) whenever that changes, starting with the
+    /// current value. Dropping the iterator stops observing `seeking`.
+    ///
+    /// # Panics
+    /// If `seeking` has been previously set to be observed.
+    pub fn seeking_events(&self) -> Result<impl Iterator<Item = bool> + '_> {
+        let mut last = None;
+        Ok(self
+            .observe_property_with_initial("seeking", Format::Flag)?
+            .filter_property_bool("seeking")
+            .filter(move |&seeking| last.replace(seeking) != Some(seeking)))
+    }
+
+    #[inline]
+    /// Block until an event of the same variant as `event` arrives, or at most `timeout`, and
+    /// return it. For `PropertyChange` and `LogMessage`, the name and the level of `event` are
+    /// used like in `observe_events`. Since mpv reports the current value of a property once it
+    /// is observed, waiting for a `PropertyChange` returns it right away.
+    ///
+    /// This doesn't affect any `EventIter`, not even one that observes the same `Event`. Returns
+    /// `Error::Timeout` if no such event arrived in time, and `Error::CommandFailed` if mpv
+    /// shut down first.
+    pub fn wait_for_event(&self, event: Event, timeout: Duration) -> Result<Event> {
+        let client = SubClient::new(self)?;
+        match event {
+            Event::PropertyChange { ref name, ref data } => {
+                let name = CString::new(&name[..])?;
+                mpv_err((), unsafe {
+                    mpv_sys::mpv_observe_property(
+                        client.0.as_ptr(),
+                        0,
+                        name.as_ptr(),
+                        data.format().as_raw(),
+                    )
+                })?;
+            }
+            Event::LogMessage { level, .. } => {
+                let min_level = CString::new(level.to_string())?;
+                mpv_err((), unsafe {
+                    mpv_sys::mpv_request_log_messages(client.0.as_ptr(), min_level.as_ptr())
+                })?;
+            }
+            _ => {}
+        }
+
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            let raw =
+                unsafe { &*mpv_sys::mpv_wait_event(client.0.as_ptr(), wait_timeout(deadline)?) };
+            if raw.event_id == event.as_id() {
+                return Ok(Event::from_raw(raw, client.0));
+            } else if raw.event_id == mpv_event_id::Shutdown {
+                return Err(Error::CommandFailed);
+            }
+        }
+    }
+
+    #[inline]
+    /// Observe given `Event`s via an `OwnedEventIter`, which keeps the `Mpv` alive and can be
+    /// accessed through `OwnedEventIter::mpv`.
+    ///
+    /// # Panics
+    /// If an event is set to be observed that has been previously set to be observed.
+    pub fn into_events(self, events: &[Event]) -> Result<OwnedEventIter> {
+        OwnedEventIter::new(Arc::new(self), events)
+    }
+
+    fn event_iter(&self, local_to_observe: Vec<Event>) -> EventIter<'_> {
+        EventIter {
             ctx: self.ctx,
-            first_iteration: true,
+            first_iteration: Cell::new(true),
+            exhausted: Cell::new(false),
+            initial: RefCell::new(Vec::new()),
             notification: &self.ev_iter_notification,
             all_to_observe: &self.ev_to_observe,
             all_to_observe_properties: &self.ev_to_observe_properties,
-            local_to_observe: evs,
+            local_to_observe,
             all_observed: &self.ev_observed,
+            shutdown: &self.ev_shutdown,
+            log_level: &self.log_level,
             _does_not_outlive: PhantomData::<&Self>,
-        })
+        }
     }
 }
 
@@ -184,22 +412,53 @@ pub enum Event {
         level: LogLevel,
         text: String,
     },
-    StartFile,
+    /// `playlist_entry_id` is 0 if libmpv is too old to report it.
+    StartFile {
+        playlist_entry_id: i64,
+    },
+    /// `playlist_entry_id` is 0 if libmpv is too old to report it.
     EndFile {
         reason: EndFileReason,
         error: Option<Error>,
+        playlist_entry_id: i64,
+    },
+    /// The data is read right after the file was loaded, and ignored when observing this.
+    /// `duration` is `None` e.g. for live streams.
+    FileLoaded {
+        tracks: Vec<Track>,
+        duration: Option<f64>,
+        filename: String,
     },
-    FileLoaded,
     Idle,
     Tick,
-    VideoReconfig,
+    /// `params` is `None` if the video was disabled, and ignored when observing this.
+    VideoReconfig {
+        params: Option<VideoParams>,
+    },
     AudioReconfig,
     Seek,
     PlaybackRestart,
+    /// The reply to `Mpv::command_node_async`, carrying the result or error of the command.
+    CommandReply {
+        reply_userdata: u64,
+        result: Result<MpvNode>,
+    },
     PropertyChange {
         name: String,
         data: PropertyData,
     },
+    /// The event queue of mpv overflowed, so events may have been lost.
+    /// This is always returned by the `EventIter` that noticed it, and does not need to be observed.
+    QueueOverflow,
+    /// The mpv core is shutting down, e.g. because of `Mpv::quit`, after which most functions
+    /// return errors. Every `EventIter` returns this once and is exhausted afterwards. Like
+    /// `QueueOverflow`, this does not need to be observed.
+    Shutdown,
+    /// An event that this crate doesn't know about, e.g. one added by a newer libmpv.
+    #[cfg(feature = "unknown_events")]
+    Unknown {
+        id: EventId,
+    },
 }
 
 impl Event {
@@ -221,64 +480,111 @@ impl Event {
         }
     }
 
+    /// Create an empty `Event::CommandReply`.
+    /// Use this to observe the replies to `Mpv::command_node_async`.
+    pub fn empty_command_reply() -> Event {
+        Event::CommandReply {
+            reply_userdata: 0,
+            result: Ok(MpvNode::None),
+        }
+    }
+
     fn as_id(&self) -> EventId {
         match *self {
             Event::LogMessage { .. } => mpv_event_id::LogMessage,
-            Event::StartFile => mpv_event_id::StartFile,
+            Event::StartFile { .. } => mpv_event_id::StartFile,
             Event::EndFile { .. } => mpv_event_id::EndFile,
-            Event::FileLoaded => mpv_event_id::FileLoaded,
+            Event::FileLoaded { .. } => mpv_event_id::FileLoaded,
             Event::Idle => mpv_event_id::Idle,
             Event::Tick => mpv_event_id::Tick,
-            Event::VideoReconfig => mpv_event_id::VideoReconfig,
+            Event::VideoReconfig { .. } => mpv_event_id::VideoReconfig,
             Event::AudioReconfig => mpv_event_id::AudioReconfig,
             Event::Seek => mpv_event_id::Seek,
             Event::PlaybackRestart => mpv_event_id::PlaybackRestart,
+            Event::CommandReply { .. } => mpv_event_id::CommandReply,
             Event::PropertyChange { .. } => mpv_event_id::PropertyChange,
+            Event::QueueOverflow => mpv_event_id::QueueOverflow,
+            Event::Shutdown => mpv_event_id::Shutdown,
+            #[cfg(feature = "unknown_events")]
+            Event::Unknown { id } => id,
         }
     }
 
     fn structural_eq(&self, rhs: &Event) -> bool {
         match (self, rhs) {
             (&Event::LogMessage { .. }, &Event::LogMessage { .. })
-            | (&Event::StartFile, &Event::StartFile)
+            | (&Event::StartFile { .. }, &Event::StartFile { .. })
             | (&Event::EndFile { .. }, &Event::EndFile { .. })
-            | (&Event::FileLoaded, &Event::FileLoaded)
+            | (&Event::FileLoaded { .. }, &Event::FileLoaded { .. })
             | (&Event::Idle, &Event::Idle)
             | (&Event::Tick, &Event::Tick)
-            | (&Event::VideoReconfig, &Event::VideoReconfig)
+            | (&Event::VideoReconfig { .. }, &Event::VideoReconfig { .. })
             | (&Event::AudioReconfig, &Event::AudioReconfig)
             | (&Event::Seek, &Event::Seek)
             | (&Event::PlaybackRestart, &Event::PlaybackRestart)
-            | (&Event::PropertyChange { .. }, &Event::PropertyChange { .. }) => true,
+            | (&Event::CommandReply { .. }, &Event::CommandReply { .. })
+            | (&Event::PropertyChange { .. }, &Event::PropertyChange { .. })
+            | (&Event::QueueOverflow, &Event::QueueOverflow)
+            | (&Event::Shutdown, &Event::Shutdown) => true,
+            #[cfg(feature = "unknown_events")]
+            (&Event::Unknown { id }, &Event::Unknown { id: rhs_id }) => id == rhs_id,
             _ => false,
         }
     }
 
-    fn from_raw(raw: &mpv_sys::mpv_event) -> Event {
+    // `ctx` is the handle that returned `raw`, which is needed to read the parameters of
+    // `VideoReconfig`.
+    pub(crate) fn from_raw(raw: &mpv_sys::mpv_event, ctx: NonNull<mpv_sys::mpv_handle>) -> Event {
+        if raw.event_id == mpv_event_id::CommandReply {
+            return Event::CommandReply {
+                reply_userdata: raw.reply_userdata,
+                result: unsafe { command_reply_result(raw) },
+            };
+        }
         assert!(mpv_err((), raw.error).is_ok());
         match raw.event_id {
             mpv_event_id::LogMessage => Event::logmessage_from_mpv_sys(raw.data),
-            mpv_event_id::StartFile => Event::StartFile,
+            mpv_event_id::StartFile => Event::StartFile {
+                playlist_entry_id: unsafe { start_file_entry_id(raw.data) },
+            },
             mpv_event_id::EndFile => Event::endfile_from_mpv_sys(raw.data),
-            mpv_event_id::FileLoaded => Event::FileLoaded,
+            mpv_event_id::FileLoaded => {
+                let FileLoadedInfo {
+                    tracks,
+                    duration,
+                    filename,
+                } = FileLoadedInfo::from_ctx(ctx);
+                Event::FileLoaded {
+                    tracks,
+                    duration,
+                    filename,
+                }
+            }
             mpv_event_id::Idle => Event::Idle,
             mpv_event_id::Tick => Event::Tick,
-            mpv_event_id::VideoReconfig => Event::VideoReconfig,
+            mpv_event_id::VideoReconfig => Event::VideoReconfig {
+                params: VideoParams::from_ctx(ctx),
+            },
             mpv_event_id::AudioReconfig => Event::AudioReconfig,
             mpv_event_id::Seek => Event::Seek,
             mpv_event_id::PlaybackRestart => Event::PlaybackRestart,
             mpv_event_id::PropertyChange => Event::property_from_mpv_sys(raw.data),
+            mpv_event_id::QueueOverflow => Event::QueueOverflow,
+            mpv_event_id::Shutdown => Event::Shutdown,
+            #[cfg(feature = "unknown_events")]
+            id => Event::Unknown { id },
+            #[cfg(not(feature = "unknown_events"))]
             _ => unreachable!(),
         }
     }
 
     fn endfile_from_mpv_sys(raw: *mut ctype::c_void) -> Event {
         assert!(!raw.is_null());
+        let playlist_entry_id = unsafe { end_file_entry_id(raw) };
         let raw = unsafe { &mut *(raw as *mut mpv_sys::mpv_event_end_file) };
 
-        assert!(raw.reason.is_positive());
         Event::EndFile {
-            reason: raw.reason as _,
+            reason: EndFileReason::from_raw(raw.reason),
             error: {
                 let err = mpv_err((), raw.error);
                 if err.is_err() {
@@ -287,6 +593,7 @@ impl Event {
                     None
                 }
             },
+            playlist_entry_id,
         }
     }
 
@@ -295,7 +602,7 @@ impl Event {
         let raw = unsafe { &mut *(raw as *mut mpv_sys::mpv_event_log_message) };
         Event::LogMessage {
             prefix: unsafe { mpv_cstr_to_str!(raw.prefix).unwrap().into() },
-            level: raw.log_level,
+            level: LogLevel::from_raw(raw.log_level),
             text: unsafe { mpv_cstr_to_str!(raw.text).unwrap().into() },
         }
     }
@@ -305,9 +612,139 @@ impl Event {
         let raw = unsafe { &mut *(raw as *mut mpv_sys::mpv_event_property) };
         Event::PropertyChange {
             name: unsafe { mpv_cstr_to_str!(raw.name).unwrap().into() },
-            data: PropertyData::from_raw(raw.format, raw.data),
+            data: PropertyData::from_raw(MpvFormat::from_raw(raw.format), raw.data),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// A builder for the `Event`s passed to `Mpv::observe_events`, e.g.
+/// `Events::new().property("pause", Format::Flag).log(LogLevel::Info).build()`.
+pub struct Events {
+    events: Vec<Event>,
+    error: Option<Error>,
+}
+
+impl Events {
+    #[inline]
+    /// Create an empty list of events.
+    pub fn new() -> Events {
+        Events::default()
+    }
+
+    #[inline]
+    /// Observe changes of the property `name` in `format`.
+    ///
+    /// `build` returns `Error::InvalidArgument` for `Format::Node`, which `PropertyData` can't
+    /// hold.
+    pub fn property(mut self, name: &str, format: Format) -> Events {
+        match PropertyData::placeholder(format) {
+            Ok(data) => self.event(Event::PropertyChange {
+                name: name.to_owned(),
+                data,
+            }),
+            Err(e) => {
+                self.error.get_or_insert(e);
+                self
+            }
         }
     }
+
+    #[inline]
+    /// Observe the property `name` only to be notified that it changed, see `PropertyData::None`.
+    pub fn property_notify(self, name: &str) -> Events {
+        self.event(Event::PropertyChange {
+            name: name.to_owned(),
+            data: PropertyData::None,
+        })
+    }
+
+    #[inline]
+    /// Observe log messages of at least `level`.
+    pub fn log(self, level: LogLevel) -> Events {
+        self.event(Event::empty_logmessage(level))
+    }
+
+    #[inline]
+    /// Observe `Event::StartFile`.
+    pub fn start_file(self) -> Events {
+        self.event(Event::StartFile {
+            playlist_entry_id: 0,
+        })
+    }
+
+    #[inline]
+    /// Observe `Event::EndFile`.
+    pub fn end_file(self) -> Events {
+        self.event(Event::EndFile {
+            reason: EndFileReason::Eof,
+            error: None,
+            playlist_entry_id: 0,
+        })
+    }
+
+    #[inline]
+    /// Observe `Event::FileLoaded`.
+    pub fn file_loaded(self) -> Events {
+        self.event(Event::FileLoaded {
+            tracks: Vec::new(),
+            duration: None,
+            filename: String::new(),
+        })
+    }
+
+    #[inline]
+    /// Observe `Event::Seek`.
+    pub fn seek(self) -> Events {
+        self.event(Event::Seek)
+    }
+
+    #[inline]
+    /// Observe `Event::PlaybackRestart`.
+    pub fn playback_restart(self) -> Events {
+        self.event(Event::PlaybackRestart)
+    }
+
+    #[inline]
+    /// Observe `Event::CommandReply`.
+    pub fn command_reply(self) -> Events {
+        self.event(Event::empty_command_reply())
+    }
+
+    #[inline]
+    /// Observe `Event::Idle`.
+    pub fn idle(self) -> Events {
+        self.event(Event::Idle)
+    }
+
+    #[inline]
+    /// Observe any other `Event`.
+    pub fn event(mut self, event: Event) -> Events {
+        self.events.push(event);
+        self
+    }
+
+    #[inline]
+    /// Return the events, or `Error::InvalidArgument` if an event or property was added twice,
+    /// which `Mpv::observe_events` would panic on.
+    pub fn build(self) -> Result<Vec<Event>> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        for (i, ev) in self.events.iter().enumerate() {
+            let duplicate = self.events[..i].iter().any(|prev| match (prev, ev) {
+                (
+                    Event::PropertyChange { name, .. },
+                    Event::PropertyChange { name: ev_name, .. },
+                ) => name == ev_name,
+                _ => prev.structural_eq(ev),
+            });
+            if duplicate {
+                return Err(Error::InvalidArgument);
+            }
+        }
+        Ok(self.events)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -319,120 +756,466 @@ pub enum PropertyData {
     Flag(bool),
     Int64(i64),
     Double(ctype::c_double),
+    /// No data, observing a property with this only notifies that it changed.
+    None,
 }
 
 impl PropertyData {
+    // The data to observe a property in `format` with, its value is ignored.
+    fn placeholder(format: Format) -> Result<PropertyData> {
+        Ok(match format {
+            Format::String => PropertyData::String(String::new()),
+            Format::Flag => PropertyData::Flag(false),
+            Format::Int64 => PropertyData::Int64(0),
+            Format::Double => PropertyData::Double(0.),
+            Format::Node => return Err(Error::InvalidArgument),
+        })
+    }
+
     fn format(&self) -> MpvFormat {
         match *self {
-            PropertyData::String(_) => mpv_format::String,
-            PropertyData::OsdString(_) => mpv_format::OsdString,
-            PropertyData::Flag(_) => mpv_format::Flag,
-            PropertyData::Int64(_) => mpv_format::Int64,
-            PropertyData::Double(_) => mpv_format::Double,
+            PropertyData::String(_) => MpvFormat::String,
+            PropertyData::OsdString(_) => MpvFormat::OsdString,
+            PropertyData::Flag(_) => MpvFormat::Flag,
+            PropertyData::Int64(_) => MpvFormat::Int64,
+            PropertyData::Double(_) => MpvFormat::Double,
+            PropertyData::None => MpvFormat::None,
         }
     }
 
     fn from_raw(fmt: MpvFormat, ptr: *mut ctype::c_void) -> PropertyData {
+        PropertyDataRef::from_raw(fmt, ptr).to_property_data()
+    }
+
+    // Set property `name` to this value, see `Mpv::set_properties`.
+    pub(crate) fn set_on(&self, mpv: &Mpv, name: &str) -> Result<()> {
+        match *self {
+            PropertyData::String(ref v) => mpv.set_property(name, &**v),
+            PropertyData::OsdString(_) | PropertyData::None => Err(Error::InvalidArgument),
+            PropertyData::Flag(v) => mpv.set_property(name, v),
+            PropertyData::Int64(v) => mpv.set_property(name, v),
+            PropertyData::Double(v) => mpv.set_property(name, v),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]
+/// Data of an `EventRef::PropertyChange`, like `PropertyData`, but borrowing strings.
+pub enum PropertyDataRef<'a> {
+    String(&'a str),
+    OsdString(&'a str),
+    Flag(bool),
+    Int64(i64),
+    Double(ctype::c_double),
+    None,
+}
+
+impl<'a> PropertyDataRef<'a> {
+    #[inline]
+    /// Copy the data into an owned `PropertyData`.
+    pub fn to_property_data(&self) -> PropertyData {
+        match *self {
+            PropertyDataRef::String(v) => PropertyData::String(v.to_owned()),
+            PropertyDataRef::OsdString(v) => PropertyData::OsdString(v.to_owned()),
+            PropertyDataRef::Flag(v) => PropertyData::Flag(v),
+            PropertyDataRef::Int64(v) => PropertyData::Int64(v),
+            PropertyDataRef::Double(v) => PropertyData::Double(v),
+            PropertyDataRef::None => PropertyData::None,
+        }
+    }
+
+    // Decode the data of a property in `fmt`, whose strings stay valid until the next call of
+    // `mpv_wait_event`.
+    fn from_raw(fmt: MpvFormat, ptr: *mut ctype::c_void) -> PropertyDataRef<'a> {
+        if fmt == MpvFormat::None {
+            return PropertyDataRef::None;
+        }
         assert!(!ptr.is_null());
         match fmt {
-            mpv_format::Flag => PropertyData::Flag(unsafe { *(ptr as *mut i64) } != 0),
-            mpv_format::Int64 => PropertyData::Int64(unsafe { *(ptr as *mut _) }),
-            mpv_format::Double => PropertyData::Double(unsafe { *(ptr as *mut _) }),
-            _ => unreachable!(),
+            MpvFormat::Flag => PropertyDataRef::Flag(unsafe { *(ptr as *mut ctype::c_int) } != 0),
+            MpvFormat::Int64 => PropertyDataRef::Int64(unsafe { *(ptr as *mut _) }),
+            MpvFormat::Double => PropertyDataRef::Double(unsafe { *(ptr as *mut _) }),
+            MpvFormat::String => {
+                let char_ptr = unsafe { *(ptr as *mut *mut ctype::c_char) };
+                PropertyDataRef::String(unsafe { mpv_cstr_to_str!(char_ptr).unwrap() })
+            }
+            MpvFormat::OsdString => {
+                let char_ptr = unsafe { *(ptr as *mut *mut ctype::c_char) };
+                PropertyDataRef::OsdString(unsafe { mpv_cstr_to_str!(char_ptr).unwrap() })
+            }
+            // Properties are never observed in the other formats.
+            MpvFormat::None
+            | MpvFormat::Node
+            | MpvFormat::NodeArray
+            | MpvFormat::NodeMap
+            | MpvFormat::ByteArray
+            | MpvFormat::Unknown(_) => PropertyDataRef::None,
         }
     }
 }
 
-fn mpv_log_level_as_str(lvl: LogLevel) -> &'static str {
-    match lvl {
-        mpv_log_level::None => "no",
-        mpv_log_level::Fatal => "fatal",
-        mpv_log_level::Error => "error",
-        mpv_log_level::Warn => "warn",
-        mpv_log_level::Info => "info",
-        mpv_log_level::V => "v",
-        mpv_log_level::Debug => "debug",
-        mpv_log_level::Trace => "trace",
-        _ => unreachable!(),
+impl<'a> From<&'a PropertyData> for PropertyDataRef<'a> {
+    fn from(data: &'a PropertyData) -> PropertyDataRef<'a> {
+        match *data {
+            PropertyData::String(ref v) => PropertyDataRef::String(v),
+            PropertyData::OsdString(ref v) => PropertyDataRef::OsdString(v),
+            PropertyData::Flag(v) => PropertyDataRef::Flag(v),
+            PropertyData::Int64(v) => PropertyDataRef::Int64(v),
+            PropertyData::Double(v) => PropertyDataRef::Double(v),
+            PropertyData::None => PropertyDataRef::None,
+        }
     }
 }
 
-/// A blocking `Iterator` over some observed events of an `Mpv` instance.
-/// Once the `EventIter` is dropped, it's `Event`s are removed from
-/// the "to be observed" queue, therefore new `Event` invocations won't be observed.
-pub struct EventIter<'parent> {
-    ctx: NonNull<mpv_sys::mpv_handle>,
-    first_iteration: bool,
-    notification: &'parent (Mutex<bool>, Condvar),
-    all_to_observe: &'parent Mutex<Vec<Event>>,
-    all_to_observe_properties: &'parent Mutex<HashMap<String, u64>>,
-    local_to_observe: Vec<Event>,
-    all_observed: &'parent Mutex<Vec<Event>>,
-    _does_not_outlive: PhantomData<&'parent Mpv>,
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]
+/// An `Event` that borrows its strings, as passed to `EventIter::for_each_ref`.
+pub enum EventRef<'a> {
+    LogMessage {
+        prefix: &'a str,
+        level: LogLevel,
+        text: &'a str,
+    },
+    PropertyChange {
+        name: &'a str,
+        data: PropertyDataRef<'a>,
+    },
+    /// Any other `Event`, which holds no strings.
+    Other(Event),
 }
 
-impl<'parent> Drop for EventIter<'parent> {
-    fn drop(&mut self) {
-        let mut all_to_observe = self.all_to_observe.lock();
-        let mut all_observed = self.all_observed.lock();
-        let mut all_to_observe_properties = self.all_to_observe_properties.lock();
+impl<'a> EventRef<'a> {
+    #[inline]
+    /// Copy the event into an owned `Event`.
+    pub fn to_event(&self) -> Event {
+        match *self {
+            EventRef::LogMessage {
+                prefix,
+                level,
+                text,
+            } => Event::LogMessage {
+                prefix: prefix.to_owned(),
+                level,
+                text: text.to_owned(),
+            },
+            EventRef::PropertyChange { name, ref data } => Event::PropertyChange {
+                name: name.to_owned(),
+                data: data.to_property_data(),
+            },
+            EventRef::Other(ref ev) => ev.clone(),
+        }
+    }
 
-        // Returns true if outer and inner event match, if so, the event is unobserved.
-        let mut compare_ev_unobserve = |outer_ev: &Event, inner_ev: &Event| {
-            if let Event::PropertyChange { ref name, .. } = *outer_ev {
-                let oname = name;
-                if let Event::PropertyChange { ref name, .. } = *inner_ev {
-                    if oname == name {
-                        unsafe {
-                            mpv_sys::mpv_unobserve_property(
-                                self.ctx.as_ptr(),
-                                all_to_observe_properties.remove(oname).unwrap(),
-                            );
-                        }
-                        return true;
-                    }
-                } else if mpv_event_id::LogMessage == outer_ev.as_id()
-                    && mpv_event_id::LogMessage == inner_ev.as_id()
-                {
-                    let min_level = &*b"none\0";
-                    unsafe {
-                        mpv_sys::mpv_request_log_messages(
-                            self.ctx.as_ptr(),
-                            min_level.as_ptr() as _,
-                        )
-                    };
-                    return true;
+    fn from_raw(raw: &'a mpv_sys::mpv_event, ctx: NonNull<mpv_sys::mpv_handle>) -> EventRef<'a> {
+        match raw.event_id {
+            mpv_event_id::LogMessage => {
+                assert!(!raw.data.is_null());
+                let raw = unsafe { &*(raw.data as *const mpv_sys::mpv_event_log_message) };
+                EventRef::LogMessage {
+                    prefix: unsafe { mpv_cstr_to_str!(raw.prefix).unwrap() },
+                    level: LogLevel::from_raw(raw.log_level),
+                    text: unsafe { mpv_cstr_to_str!(raw.text).unwrap() },
                 }
-            } else if outer_ev.structural_eq(inner_ev) {
-                unsafe { mpv_sys::mpv_request_event(self.ctx.as_ptr(), inner_ev.as_id(), 0) };
-                return true;
             }
-            false
-        };
-
-        // This removes all events for which compare_ev_unobserve returns true.
-        for outer_ev in &self.local_to_observe {
-            all_to_observe.retain(|inner_ev| !compare_ev_unobserve(outer_ev, inner_ev));
-            all_observed.retain(|inner_ev| !compare_ev_unobserve(outer_ev, inner_ev));
+            mpv_event_id::PropertyChange => {
+                assert!(!raw.data.is_null());
+                let raw = unsafe { &*(raw.data as *const mpv_sys::mpv_event_property) };
+                EventRef::PropertyChange {
+                    name: unsafe { mpv_cstr_to_str!(raw.name).unwrap() },
+                    data: PropertyDataRef::from_raw(MpvFormat::from_raw(raw.format), raw.data),
+                }
+            }
+            _ => EventRef::Other(Event::from_raw(raw, ctx)),
         }
     }
 }
 
-impl<'parent> Iterator for EventIter<'parent> {
-    type Item = Vec<Event>;
+impl<'a> From<&'a Event> for EventRef<'a> {
+    fn from(ev: &'a Event) -> EventRef<'a> {
+        match *ev {
+            Event::LogMessage {
+                ref prefix,
+                level,
+                ref text,
+            } => EventRef::LogMessage {
+                prefix,
+                level,
+                text,
+            },
+            Event::PropertyChange { ref name, ref data } => EventRef::PropertyChange {
+                name,
+                data: data.into(),
+            },
+            _ => EventRef::Other(ev.clone()),
+        }
+    }
+}
+
+/// Types that can be converted from and to `PropertyData`.
+///
+/// These can also be used with `Mpv::get_property` and `Mpv::set_property`.
+pub trait PropertyType: GetData + SetData + Sized + Send + 'static {
+    /// Returns `Error::Raw(mpv_error::PropertyFormat)` if `data` is of another type.
+    fn from_property_data(data: PropertyData) -> Result<Self>;
+    #[allow(missing_docs)]
+    fn into_property_data(self) -> PropertyData;
+}
+
+impl PropertyType for bool {
+    fn from_property_data(data: PropertyData) -> Result<bool> {
+        match data {
+            PropertyData::Flag(v) => Ok(v),
+            _ => Err(Error::Raw(mpv_error::PropertyFormat)),
+        }
+    }
+
+    fn into_property_data(self) -> PropertyData {
+        PropertyData::Flag(self)
+    }
+}
+
+impl PropertyType for i64 {
+    fn from_property_data(data: PropertyData) -> Result<i64> {
+        match data {
+            PropertyData::Int64(v) => Ok(v),
+            _ => Err(Error::Raw(mpv_error::PropertyFormat)),
+        }
+    }
+
+    fn into_property_data(self) -> PropertyData {
+        PropertyData::Int64(self)
+    }
+}
+
+impl PropertyType for f64 {
+    fn from_property_data(data: PropertyData) -> Result<f64> {
+        match data {
+            PropertyData::Double(v) => Ok(v),
+            _ => Err(Error::Raw(mpv_error::PropertyFormat)),
+        }
+    }
+
+    fn into_property_data(self) -> PropertyData {
+        PropertyData::Double(self)
+    }
+}
+
+impl PropertyType for String {
+    /// Both `String` and `OsdString` are accepted.
+    fn from_property_data(data: PropertyData) -> Result<String> {
+        match data {
+            PropertyData::String(v) | PropertyData::OsdString(v) => Ok(v),
+            _ => Err(Error::Raw(mpv_error::PropertyFormat)),
+        }
+    }
+
+    fn into_property_data(self) -> PropertyData {
+        PropertyData::String(self)
+    }
+}
+
+// Whether the property of the `PropertyChange` event `event` is observed as `PropertyData::None`.
+fn notify_only(to_observe: &[Event], event: &mpv_sys::mpv_event) -> bool {
+    let raw = unsafe { &*(event.data as *mut mpv_sys::mpv_event_property) };
+    let raw_name = unsafe { mpv_cstr_to_str!(raw.name) }.ok();
+    to_observe.iter().any(|ev| match ev {
+        Event::PropertyChange {
+            name,
+            data: PropertyData::None,
+        } => raw_name == Some(name.as_str()),
+        _ => false,
+    })
+}
+
+/// A blocking `Iterator` over some observed events of an `Mpv` instance.
+/// Once the `EventIter` is dropped, it's `Event`s are removed from
+/// the "to be observed" queue, therefore new `Event` invocations won't be observed.
+pub struct EventIter<'parent> {
+    ctx: NonNull<mpv_sys::mpv_handle>,
+    // These are mutated by `next_batch`, which can be called through `EventIter::iter`.
+    first_iteration: Cell<bool>,
+    // Set once `Event::Shutdown` was returned.
+    exhausted: Cell<bool>,
+    // Events to yield before any of libmpv, see `Mpv::observe_property_with_initial`.
+    initial: RefCell<Vec<Event>>,
+    notification: &'parent (Mutex<bool>, Condvar),
+    all_to_observe: &'parent Mutex<Vec<Event>>,
+    all_to_observe_properties: &'parent Mutex<HashMap<String, u64>>,
+    local_to_observe: Vec<Event>,
+    all_observed: &'parent Mutex<Vec<Event>>,
+    shutdown: &'parent AtomicBool,
+    log_level: &'parent AtomicU32,
+    _does_not_outlive: PhantomData<&'parent Mpv>,
+}
+
+impl<'parent> fmt::Debug for EventIter<'parent> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventIter")
+            .field("ctx", &format_args!("<mpv_handle>"))
+            .field("first_iteration", &self.first_iteration.get())
+            .field("exhausted", &self.exhausted.get())
+            .field("local_to_observe", &self.local_to_observe)
+            .finish()
+    }
+}
+
+/// The registration of an `EventIter`, which keeps its `Event`s observed without borrowing
+/// the `Mpv` instance.
+///
+/// Observed `Event`s are queued until the token is passed to `Mpv::resume_events` or
+/// `Mpv::unobserve`, so it shouldn't be held onto indefinitely.
+#[must_use = "the events stay observed until the token is passed to Mpv::unobserve"]
+pub struct ObserveToken {
+    ctx: NonNull<mpv_sys::mpv_handle>,
+    events: Vec<Event>,
+}
+
+impl<'parent> EventIter<'parent> {
+    #[inline]
+    /// Detach from the `Mpv` instance, without unobserving the `Event`s of this `EventIter`.
+    ///
+    /// An initial value of `Mpv::observe_property_with_initial` that wasn't yielded yet is lost.
+    pub fn into_token(mut self) -> ObserveToken {
+        ObserveToken {
+            ctx: self.ctx,
+            events: mem::take(&mut self.local_to_observe),
+        }
+    }
+
+    #[inline]
+    /// The number of observed `Event`s that are queued for this `EventIter`, without consuming
+    /// them. If any are queued, `next` doesn't block.
+    ///
+    /// Events that are still in the queue of libmpv are not counted.
+    pub fn peek_pending(&self) -> usize {
+        self.all_observed
+            .lock()
+            .iter()
+            .filter(|ev| self.observes(ev))
+            .count()
+    }
+
+    #[inline]
+    /// Drop all observed `Event`s that are currently queued for this `EventIter`, including those
+    /// still in the queue of libmpv, without blocking. Returns how many were dropped.
+    ///
+    /// Events of other `EventIter`s are kept for them. `Event::Shutdown` is never dropped, but
+    /// returned by the next call of `next`.
+    pub fn discard_pending(&mut self) -> usize {
+        if self.exhausted.get() {
+            return 0;
+        }
+        let initial = self.initial.get_mut();
+        let mut discarded = initial.len();
+        initial.clear();
+
+        let mut observed = self.all_observed.lock();
+        let len = observed.len();
+        observed.retain(|ev| !self.observes(ev));
+        discarded += len - observed.len();
+
+        let all_to_observe = self.all_to_observe.lock();
+        loop {
+            let event = unsafe { &*mpv_sys::mpv_wait_event(self.ctx.as_ptr(), 0f32 as _) };
+            let ev_id = event.event_id;
+
+            if ev_id == mpv_event_id::None {
+                break;
+            } else if ev_id == mpv_event_id::Shutdown {
+                self.initial.get_mut().push(Event::Shutdown);
+                self.broadcast_shutdown();
+                break;
+            } else if ev_id == mpv_event_id::PropertyChange
+                && MpvFormat::from_raw(unsafe {
+                    (*(event.data as *mut mpv_sys::mpv_event_property)).format
+                }) == MpvFormat::None
+                && !notify_only(&all_to_observe, event)
+            {
+                continue;
+            }
+
+            if all_to_observe
+                .iter()
+                .any(|all_ob_ev| ev_id == all_ob_ev.as_id())
+            {
+                // Compared by property name, so changes of other properties are kept.
+                let ev = Event::from_raw(event, self.ctx);
+                if self.observes(&ev) {
+                    discarded += 1;
+                } else {
+                    observed.push(ev);
+                }
+            }
+        }
+        if !observed.is_empty() {
+            drop(observed);
+            self.notification.1.notify_all();
+        }
+        discarded
+    }
+
+    #[inline]
+    /// An `Iterator` over the batches of this `EventIter` that only borrows it, so that it can be
+    /// consumed from multiple places, e.g. several `for` loops.
+    pub fn iter(&self) -> EventIterRef<'_, 'parent> {
+        EventIterRef { iter: self }
+    }
+
+    #[inline]
+    /// Only yield the `Event`s for which `pred` returns true. The others are still taken from
+    /// the queue, and batches that end up empty are skipped.
+    pub fn with_filter<F: Fn(&Event) -> bool + 'static>(
+        self,
+        pred: F,
+    ) -> FilteredEventIter<'parent, F> {
+        FilteredEventIter { iter: self, pred }
+    }
+
+    #[inline]
+    /// Like `for_each` over the batches of events, but pass borrowed events to `f` instead of
+    /// allocating owned ones, until `Event::Shutdown` was passed.
+    ///
+    /// `f` is called while `Event`s are queued for other `EventIter`s, so it must not observe or
+    /// unobserve any `Event`, or call `next` of another `EventIter` of the same `Mpv` instance.
+    pub fn for_each_ref<F: FnMut(EventRef)>(&mut self, mut f: F) {
+        while self.next_batch(&mut f) {}
+    }
+
+    // Pass the next batch of events to `f`, blocking until there is one. Returns false once
+    // exhausted.
+    fn next_batch<F: FnMut(EventRef)>(&self, mut f: F) -> bool {
+        if self.exhausted.get() {
+            return false;
+        }
+        let initial = self.initial.replace(Vec::new());
+        if !initial.is_empty() {
+            for ev in initial {
+                if let Event::Shutdown = ev {
+                    // Queued by `discard_pending`.
+                    self.exhausted.set(true);
+                }
+                f(EventRef::from(&ev));
+            }
+            return true;
+        }
 
-    fn next(&mut self) -> Option<Self::Item> {
         // Loop until some events can be returned
         loop {
             let mut observed = self.all_observed.lock();
-            if observed.is_empty() && !self.first_iteration {
+            if observed.is_empty() && !self.first_iteration.get() {
                 drop(observed);
-                self.notification.1.wait(&mut self.notification.0.lock());
+                let mut notified = self.notification.0.lock();
+                // Checked under the lock, as `broadcast_shutdown` notifies while holding it.
+                if !self.shutdown.load(Ordering::SeqCst) {
+                    self.notification.1.wait(&mut notified);
+                }
+                drop(notified);
                 observed = self.all_observed.lock();
             }
 
-            let mut ret_events = Vec::with_capacity(observed.len());
-            if observed.is_empty() || self.first_iteration {
+            let mut delivered = false;
+            if observed.is_empty() || self.first_iteration.get() {
                 let all_to_observe = self.all_to_observe.lock();
                 let mut last = false;
                 'events: loop {
@@ -440,9 +1223,27 @@ impl<'parent> Iterator for EventIter<'parent> {
                     let ev_id = event.event_id;
 
                     if ev_id == mpv_event_id::QueueOverflow {
-                        // The queue needs to be emptied asap to prevent loss of events
+                        // Let the caller know that events were lost, and keep emptying the queue
+                        // asap to prevent further loss.
                         // This should happen very rarely, as the queue size is 1k (2016-10-12)
+                        f(EventRef::Other(Event::QueueOverflow));
+                        delivered = true;
+                        continue;
+                    } else if ev_id == mpv_event_id::Shutdown {
+                        // No events follow, so return what was gathered until now.
+                        f(EventRef::Other(Event::Shutdown));
+                        delivered = true;
+                        self.exhausted.set(true);
+                        self.broadcast_shutdown();
                         break;
+                    } else if ev_id == mpv_event_id::PropertyChange
+                        && MpvFormat::from_raw(unsafe {
+                            (*(event.data as *mut mpv_sys::mpv_event_property)).format
+                        }) == MpvFormat::None
+                        && !notify_only(&all_to_observe, event)
+                    {
+                        // The property is unavailable, e.g. because the file ended.
+                        continue;
                     } else if ev_id == mpv_event_id::None {
                         if last {
                             break;
@@ -453,13 +1254,15 @@ impl<'parent> Iterator for EventIter<'parent> {
                     }
                     for local_ob_ev in &self.local_to_observe {
                         if ev_id == local_ob_ev.as_id() {
-                            ret_events.push(Event::from_raw(event));
+                            // The event is valid until the next call of `mpv_wait_event`.
+                            f(EventRef::from_raw(event, self.ctx));
+                            delivered = true;
                             continue 'events;
                         }
                     }
                     for all_ob_ev in &*all_to_observe {
                         if ev_id == all_ob_ev.as_id() {
-                            observed.push(Event::from_raw(event));
+                            observed.push(Event::from_raw(event, self.ctx));
                             continue 'events;
                         }
                     }
@@ -469,18 +1272,20 @@ impl<'parent> Iterator for EventIter<'parent> {
                     self.notification.1.notify_all();
                 }
             } else {
-                // Return true where outer_ev == inner_ev, and push inner_ev to ret_events
+                // Return true where outer_ev == inner_ev, and pass inner_ev to f
                 let mut compare_ev = |outer_ev: &Event, inner_ev: &Event| {
                     if let Event::PropertyChange { ref name, .. } = *outer_ev {
                         let oname = name;
                         if let Event::PropertyChange { ref name, .. } = *inner_ev {
                             if oname == name {
-                                ret_events.push(inner_ev.clone());
+                                f(EventRef::from(inner_ev));
+                                delivered = true;
                                 return true;
                             }
                         }
                     } else if outer_ev.structural_eq(inner_ev) {
-                        ret_events.push(inner_ev.clone());
+                        f(EventRef::from(inner_ev));
+                        delivered = true;
                         return true;
                     }
                     false
@@ -496,12 +1301,413 @@ impl<'parent> Iterator for EventIter<'parent> {
                 }
             }
 
-            self.first_iteration = false;
+            self.first_iteration.set(false);
+
+            if delivered {
+                return true;
+            } else if self.shutdown.load(Ordering::SeqCst) {
+                // Another `EventIter` received it, and none of the queued events are ours.
+                f(EventRef::Other(Event::Shutdown));
+                self.exhausted.set(true);
+                return true;
+            }
+        }
+    }
+
+    // Let all other `EventIter`s yield `Event::Shutdown`, which libmpv only sends once.
+    fn broadcast_shutdown(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _notified = self.notification.0.lock();
+        self.notification.1.notify_all();
+    }
+
+    // Whether `ev` belongs to this `EventIter`, comparing property changes by name.
+    fn observes(&self, ev: &Event) -> bool {
+        self.local_to_observe
+            .iter()
+            .any(|local_ev| match (local_ev, ev) {
+                (
+                    Event::PropertyChange { name, .. },
+                    Event::PropertyChange { name: ev_name, .. },
+                ) => name == ev_name,
+                _ => local_ev.structural_eq(ev),
+            })
+    }
+}
+
+/// A borrowing `Iterator` over the batches of an `EventIter`, see `EventIter::iter`.
+pub struct EventIterRef<'iter, 'parent> {
+    iter: &'iter EventIter<'parent>,
+}
+
+impl<'iter, 'parent> Iterator for EventIterRef<'iter, 'parent> {
+    type Item = Vec<Event>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn next(&mut self) -> Option<Vec<Event>> {
+        let mut ret_events = Vec::new();
+        if self.iter.next_batch(|ev| ret_events.push(ev.to_event())) {
+            Some(ret_events)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'iter, 'parent> IntoIterator for &'iter EventIter<'parent> {
+    type Item = Vec<Event>;
+    type IntoIter = EventIterRef<'iter, 'parent>;
+
+    #[inline]
+    fn into_iter(self) -> EventIterRef<'iter, 'parent> {
+        self.iter()
+    }
+}
+
+/// An `EventIter` that only yields the `Event`s passing a predicate, see `EventIter::with_filter`.
+pub struct FilteredEventIter<'parent, F> {
+    iter: EventIter<'parent>,
+    pred: F,
+}
+
+impl<'parent, F> FilteredEventIter<'parent, F> {
+    #[inline]
+    /// Remove the filter, keeping the `Event`s observed.
+    pub fn into_inner(self) -> EventIter<'parent> {
+        self.iter
+    }
+}
+
+impl<'parent, F: Fn(&Event) -> bool> Iterator for FilteredEventIter<'parent, F> {
+    type Item = Vec<Event>;
+
+    fn next(&mut self) -> Option<Vec<Event>> {
+        loop {
+            let mut batch = self.iter.next()?;
+            batch.retain(|ev| (self.pred)(ev));
+            if !batch.is_empty() {
+                return Some(batch);
+            }
+        }
+    }
+}
+
+/// Like `EventIter`, but owning a reference to the `Mpv` instance, so that it can be stored
+/// alongside it, see `Mpv::into_events`.
+pub struct OwnedEventIter {
+    mpv: Arc<Mpv>,
+    // `None` once `Event::Shutdown` was returned.
+    token: Option<ObserveToken>,
+}
+
+impl OwnedEventIter {
+    #[inline]
+    /// Observe given `Event`s of `mpv`, like `Mpv::observe_events`.
+    ///
+    /// # Panics
+    /// If an event is set to be observed that has been previously set to be observed.
+    pub fn new(mpv: Arc<Mpv>, events: &[Event]) -> Result<OwnedEventIter> {
+        let token = mpv.observe_events(events)?.into_token();
+        Ok(OwnedEventIter {
+            mpv,
+            token: Some(token),
+        })
+    }
+
+    #[inline]
+    /// The `Mpv` instance whose `Event`s are observed.
+    pub fn mpv(&self) -> &Arc<Mpv> {
+        &self.mpv
+    }
+}
+
+impl Iterator for OwnedEventIter {
+    type Item = Vec<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut iter = self.mpv.resume_events(self.token.take()?);
+        let ret = iter.next();
+        let token = iter.into_token();
+
+        let shutdown = match ret {
+            Some(ref evs) => evs.iter().any(|ev| ev.structural_eq(&Event::Shutdown)),
+            None => true,
+        };
+        if shutdown {
+            self.mpv.unobserve(token);
+        } else {
+            self.token = Some(token);
+        }
+        ret
+    }
+}
+
+impl Drop for OwnedEventIter {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            self.mpv.unobserve(token);
+        }
+    }
+}
+
+impl<'parent> Drop for EventIter<'parent> {
+    fn drop(&mut self) {
+        let mut all_to_observe = self.all_to_observe.lock();
+        let mut all_observed = self.all_observed.lock();
+        let mut all_to_observe_properties = self.all_to_observe_properties.lock();
+
+        // Returns true if outer and inner event match, if so, the event is unobserved.
+        let mut compare_ev_unobserve = |outer_ev: &Event, inner_ev: &Event| {
+            if let Event::PropertyChange { ref name, .. } = *outer_ev {
+                let oname = name;
+                if let Event::PropertyChange { ref name, .. } = *inner_ev {
+                    if oname == name {
+                        unsafe {
+                            mpv_sys::mpv_unobserve_property(
+                                self.ctx.as_ptr(),
+                                all_to_observe_properties.remove(oname).unwrap(),
+                            );
+                        }
+                        return true;
+                    }
+                }
+            } else if outer_ev.structural_eq(inner_ev) {
+                unsafe { mpv_sys::mpv_request_event(self.ctx.as_ptr(), inner_ev.as_id(), 0) };
+                return true;
+            }
+            false
+        };
+
+        // This removes all events for which compare_ev_unobserve returns true.
+        for outer_ev in &self.local_to_observe {
+            all_to_observe.retain(|inner_ev| !compare_ev_unobserve(outer_ev, inner_ev));
+            all_observed.retain(|inner_ev| !compare_ev_unobserve(outer_ev, inner_ev));
+        }
+
+        // Disable log messages, unless the level was changed via `Mpv::set_log_level` since.
+        for ev in &self.local_to_observe {
+            if let Event::LogMessage { level, .. } = *ev {
+                if self
+                    .log_level
+                    .compare_exchange(
+                        level.as_raw(),
+                        mpv_log_level::None,
+                        Ordering::SeqCst,
+                        Ordering::SeqCst,
+                    )
+                    .is_ok()
+                {
+                    let min_level = CString::new(LogLevel::None.to_string()).unwrap();
+                    unsafe {
+                        mpv_sys::mpv_request_log_messages(self.ctx.as_ptr(), min_level.as_ptr())
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl<'parent> Iterator for EventIter<'parent> {
+    type Item = Vec<Event>;
+
+    /// The lower bound is 1 if events for this `EventIter` are already pending, as the items are
+    /// batches of events.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.exhausted.get() {
+            return (0, Some(0));
+        }
+
+        let pending = !self.initial.borrow().is_empty() || self.peek_pending() > 0;
+        (pending as usize, None)
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter().next()
+    }
+}
+
+/// Adaptors over the `Event` batches yielded by an `EventIter`.
+///
+/// This is implemented for every such `Iterator`, so adaptors can be applied to e.g.
+/// `iter.by_ref()` to keep using the `EventIter` afterwards.
+pub trait EventAdaptors: Iterator<Item = Vec<Event>> + Sized {
+    /// Yield the values of the `Flag` property `name`, ignoring all other events.
+    fn filter_property_bool(self, name: &str) -> PropertyValues<Self, bool> {
+        PropertyValues::new(self, name, |data| {
+            if let PropertyData::Flag(v) = data {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Yield the values of the `Double` property `name`, ignoring all other events.
+    fn filter_property_f64(self, name: &str) -> PropertyValues<Self, f64> {
+        PropertyValues::new(self, name, |data| {
+            if let PropertyData::Double(v) = data {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Yield the values of the `Int64` property `name`, ignoring all other events.
+    fn filter_property_i64(self, name: &str) -> PropertyValues<Self, i64> {
+        PropertyValues::new(self, name, |data| {
+            if let PropertyData::Int64(v) = data {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Yield the values of the `String` or `OsdString` property `name`, ignoring all other
+    /// events.
+    fn filter_property_string(self, name: &str) -> PropertyValues<Self, String> {
+        PropertyValues::new(self, name, |data| match data {
+            PropertyData::String(v) | PropertyData::OsdString(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Only keep the last `PropertyChange` of each property within a batch, e.g. to skip the
+    /// intermediate values of `time-pos` while seeking. Batches are not merged.
+    fn coalesce_properties(self) -> CoalesceProperties<Self> {
+        CoalesceProperties { iter: self }
+    }
+}
+
+impl<I: Iterator<Item = Vec<Event>>> EventAdaptors for I {}
+
+/// An `Iterator` over batches with only the last change of each property, see `EventAdaptors`.
+pub struct CoalesceProperties<I> {
+    iter: I,
+}
+
+impl<I: Iterator<Item = Vec<Event>>> Iterator for CoalesceProperties<I> {
+    type Item = Vec<Event>;
+
+    fn next(&mut self) -> Option<Vec<Event>> {
+        let mut seen = Vec::new();
+        // Walk backwards, so that the last change of each property is kept at its position.
+        let mut batch = self
+            .iter
+            .next()?
+            .into_iter()
+            .rev()
+            .filter(|ev| match *ev {
+                Event::PropertyChange { ref name, .. } => {
+                    if seen.contains(name) {
+                        false
+                    } else {
+                        seen.push(name.clone());
+                        true
+                    }
+                }
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+        batch.reverse();
+        Some(batch)
+    }
+}
+
+/// Watches a single property, see `Mpv::watch_property`.
+///
+/// Dropping it stops observing the property.
+pub struct PropertyWatcher<'parent, T: PropertyType> {
+    mpv: &'parent Mpv,
+    name: String,
+    iter: EventIter<'parent>,
+    _ty: PhantomData<T>,
+}
+
+impl<'parent, T: PropertyType> PropertyWatcher<'parent, T> {
+    #[inline]
+    /// The name of the watched property.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    /// Read the current value of the property, without blocking.
+    pub fn current_value(&self) -> Result<T> {
+        self.mpv.get_property(&self.name)
+    }
+
+    #[inline]
+    /// Block until the property changes, and return its latest value. Changes that queued up in
+    /// the meantime are skipped.
+    ///
+    /// Returns `Error::CommandFailed` once `Event::Shutdown` was received.
+    pub fn changed(&mut self) -> Result<T> {
+        loop {
+            let mut latest = None;
+            let mut shutdown = false;
+            let alive = self.iter.next_batch(|ev| match ev {
+                EventRef::PropertyChange { data, .. } => latest = Some(data.to_property_data()),
+                EventRef::Other(Event::Shutdown) => shutdown = true,
+                _ => {}
+            });
+
+            if let Some(data) = latest {
+                return T::from_property_data(data);
+            } else if shutdown || !alive {
+                return Err(Error::CommandFailed);
+            }
+        }
+    }
+}
+
+/// An `Iterator` over the values of a single property, see `EventAdaptors`.
+///
+/// Changes delivered in a different format than requested are skipped.
+pub struct PropertyValues<I, T> {
+    iter: I,
+    name: String,
+    extract: fn(PropertyData) -> Option<T>,
+    pending: vec::IntoIter<T>,
+}
+
+impl<I, T> PropertyValues<I, T> {
+    fn new(iter: I, name: &str, extract: fn(PropertyData) -> Option<T>) -> PropertyValues<I, T> {
+        PropertyValues {
+            iter,
+            name: name.into(),
+            extract,
+            pending: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Vec<Event>>, T> Iterator for PropertyValues<I, T> {
+    type Item = T;
 
-            if !ret_events.is_empty() {
-                ret_events.shrink_to_fit();
-                return Some(ret_events);
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(val) = self.pending.next() {
+                return Some(val);
             }
+
+            let name = &self.name;
+            let extract = self.extract;
+            self.pending = self
+                .iter
+                .next()?
+                .into_iter()
+                .filter_map(|ev| match ev {
+                    Event::PropertyChange { name: ref n, data } if n == name => extract(data),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .into_iter();
         }
     }
 }
@@ -15,3 +15,364 @@
 // You should have received a copy of the GNU Lesser General Public
 // License along with this library; if not, write to the Free Software
 // Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA  02110-1301  USA
+
+//! This allows rendering the video of mpv yourself, e.g. into an OpenGL context.
+
+use super::*;
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::os::raw as ctype;
+use std::ptr::{self, NonNull};
+
+// The software renderer of `SoftwareRenderContext`, which the pinned `render.h` predates. A
+// libmpv without it fails to create the context.
+const MPV_RENDER_API_TYPE_SW: &[u8] = b"sw\0";
+const MPV_RENDER_PARAM_SW_SIZE: mpv_sys::mpv_render_param_type = 17;
+const MPV_RENDER_PARAM_SW_FORMAT: mpv_sys::mpv_render_param_type = 18;
+const MPV_RENDER_PARAM_SW_STRIDE: mpv_sys::mpv_render_param_type = 19;
+const MPV_RENDER_PARAM_SW_POINTER: mpv_sys::mpv_render_param_type = 20;
+
+impl Mpv {
+    #[inline]
+    /// Create a context with which the video of this `Mpv` can be rendered.
+    ///
+    /// `params` does not need to be terminated by `MPV_RENDER_PARAM_INVALID`, it is appended.
+    ///
+    /// # Safety
+    /// `params` have to be valid for `mpv_render_context_create`, see `render.h` of libmpv.
+    pub unsafe fn create_render_context(
+        &self,
+        params: &[mpv_sys::mpv_render_param],
+    ) -> Result<RenderContext<'_>> {
+        let mut params = params.to_vec();
+        params.push(mpv_sys::mpv_render_param {
+            type_: mpv_sys::mpv_render_param_type_MPV_RENDER_PARAM_INVALID,
+            data: ptr::null_mut(),
+        });
+
+        let mut ctx = ptr::null_mut();
+        mpv_err(
+            (),
+            mpv_sys::mpv_render_context_create(&mut ctx, self.ctx.as_ptr(), params.as_mut_ptr()),
+        )?;
+
+        Ok(RenderContext {
+            ctx: NonNull::new_unchecked(ctx),
+            update_callback: parking_lot::Mutex::new(None),
+            _does_not_outlive: PhantomData::<&Self>,
+        })
+    }
+
+    #[inline]
+    /// Create a context with which the video of this `Mpv` can be rendered into memory, without
+    /// a GPU API.
+    ///
+    /// Returns an error if libmpv doesn't support `MPV_RENDER_API_TYPE_SW`.
+    pub fn create_software_render_context(
+        &self,
+        format: PixelFormat,
+    ) -> Result<SoftwareRenderContext<'_>> {
+        let inner = unsafe {
+            self.create_render_context(&[mpv_sys::mpv_render_param {
+                type_: mpv_sys::mpv_render_param_type_MPV_RENDER_PARAM_API_TYPE,
+                data: MPV_RENDER_API_TYPE_SW.as_ptr() as *mut _,
+            }])?
+        };
+        Ok(SoftwareRenderContext { inner, format })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// The flags returned by `RenderContext::render_context_update`.
+pub struct UpdateFlags(u64);
+
+impl UpdateFlags {
+    /// A new video frame must be rendered.
+    pub const FRAME: UpdateFlags =
+        UpdateFlags(mpv_sys::mpv_render_update_flag_MPV_RENDER_UPDATE_FRAME as u64);
+
+    #[inline]
+    /// Whether all flags of `other` are set.
+    pub fn contains(self, other: UpdateFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    /// Whether no flags are set, i.e. nothing needs to be done.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    /// The raw bitset, which may contain flags unknown to this crate.
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+}
+
+#[allow(missing_docs)]
+/// This trait describes which parameters can be passed to `RenderContext::set_parameter`.
+///
+/// # Safety
+/// `call_as_c_void` has to pass a pointer to data of the type libmpv expects for `get_type`.
+pub unsafe trait RenderParam: Sized {
+    #[doc(hidden)]
+    fn call_as_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(self, fun: F) -> Result<T>;
+    fn get_type() -> mpv_sys::mpv_render_param_type;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Whether to render flipped, e.g. when rendering to the default framebuffer of OpenGL.
+///
+/// libmpv only accepts this when rendering, so setting it returns an error.
+pub struct FlipY(pub bool);
+
+unsafe impl RenderParam for FlipY {
+    #[inline]
+    fn call_as_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(self, mut fun: F) -> Result<T> {
+        let mut flip: ctype::c_int = if self.0 { 1 } else { 0 };
+        fun(&mut flip as *mut ctype::c_int as *mut _)
+    }
+
+    #[inline]
+    fn get_type() -> mpv_sys::mpv_render_param_type {
+        mpv_sys::mpv_render_param_type_MPV_RENDER_PARAM_FLIP_Y
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The depth of the surface in bits per channel, `0` assumes `8`. Used to control dithering.
+///
+/// libmpv only accepts this when rendering, so setting it returns an error.
+pub struct DepthId(pub i32);
+
+unsafe impl RenderParam for DepthId {
+    #[inline]
+    fn call_as_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(self, mut fun: F) -> Result<T> {
+        let mut depth: ctype::c_int = self.0;
+        fun(&mut depth as *mut ctype::c_int as *mut _)
+    }
+
+    #[inline]
+    fn get_type() -> mpv_sys::mpv_render_param_type {
+        mpv_sys::mpv_render_param_type_MPV_RENDER_PARAM_DEPTH
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// An ICC profile, which is used if the `icc-profile-auto` option is enabled.
+pub struct IccProfile<'a>(pub &'a [u8]);
+
+unsafe impl<'a> RenderParam for IccProfile<'a> {
+    #[inline]
+    fn call_as_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(self, mut fun: F) -> Result<T> {
+        let mut profile = mpv_sys::mpv_byte_array {
+            data: self.0.as_ptr() as *mut _,
+            size: self.0.len(),
+        };
+        fun(&mut profile as *mut mpv_sys::mpv_byte_array as *mut _)
+    }
+
+    #[inline]
+    fn get_type() -> mpv_sys::mpv_render_param_type {
+        mpv_sys::mpv_render_param_type_MPV_RENDER_PARAM_ICC_PROFILE
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The ambient light in lux, which can be used for automatic gamma correction.
+pub struct AmbientLight(pub i32);
+
+unsafe impl RenderParam for AmbientLight {
+    #[inline]
+    fn call_as_c_void<T, F: FnMut(*mut ctype::c_void) -> Result<T>>(self, mut fun: F) -> Result<T> {
+        let mut light: ctype::c_int = self.0;
+        fun(&mut light as *mut ctype::c_int as *mut _)
+    }
+
+    #[inline]
+    fn get_type() -> mpv_sys::mpv_render_param_type {
+        mpv_sys::mpv_render_param_type_MPV_RENDER_PARAM_AMBIENT_LIGHT
+    }
+}
+
+type UpdateCallback = Box<dyn Fn() + Send>;
+
+unsafe extern "C" fn update_callback(d: *mut ctype::c_void) {
+    (*(d as *mut UpdateCallback))();
+}
+
+/// A context with which the video of an `Mpv` can be rendered.
+///
+/// Unless stated otherwise, its functions have to be called on the render thread.
+pub struct RenderContext<'parent> {
+    ctx: NonNull<mpv_sys::mpv_render_context>,
+    update_callback: parking_lot::Mutex<Option<Box<UpdateCallback>>>,
+    _does_not_outlive: PhantomData<&'parent Mpv>,
+}
+
+impl<'parent> RenderContext<'parent> {
+    #[inline]
+    /// Set a callback that is called whenever a new frame should be rendered, after which
+    /// `render_context_update` should be called.
+    ///
+    /// The callback may be called from any thread, and must not call any mpv API itself.
+    pub fn set_update_callback<F: Fn() + Send + 'static>(&self, f: F) {
+        // Hold the lock until mpv switched over, so that the previous callback is not freed
+        // while it can still be called.
+        let mut current = self.update_callback.lock();
+        let mut callback = Box::new(Box::new(f) as UpdateCallback);
+        unsafe {
+            mpv_sys::mpv_render_context_set_update_callback(
+                self.ctx.as_ptr(),
+                Some(update_callback),
+                &mut *callback as *mut UpdateCallback as *mut _,
+            );
+        }
+        *current = Some(callback);
+    }
+
+    #[inline]
+    /// Tell mpv that the update callback was invoked, and return what should happen next.
+    ///
+    /// If `UpdateFlags::FRAME` is set, `render` should be called.
+    pub fn render_context_update(&self) -> UpdateFlags {
+        UpdateFlags(unsafe { mpv_sys::mpv_render_context_update(self.ctx.as_ptr()) })
+    }
+
+    #[inline]
+    /// Change a parameter of the render context. Not all backends support all parameters, in
+    /// which case an error is returned.
+    pub fn set_parameter<T: RenderParam>(&self, param: T) -> Result<()> {
+        param.call_as_c_void(|data| {
+            mpv_err((), unsafe {
+                mpv_sys::mpv_render_context_set_parameter(
+                    self.ctx.as_ptr(),
+                    mpv_sys::mpv_render_param {
+                        type_: T::get_type(),
+                        data,
+                    },
+                )
+            })
+        })
+    }
+
+    #[inline]
+    /// Render the video.
+    ///
+    /// `params` does not need to be terminated by `MPV_RENDER_PARAM_INVALID`, it is appended.
+    ///
+    /// # Safety
+    /// `params` have to be valid for `mpv_render_context_render`, see `render.h` of libmpv.
+    pub unsafe fn render(&self, params: &[mpv_sys::mpv_render_param]) -> Result<()> {
+        let mut params = params.to_vec();
+        params.push(mpv_sys::mpv_render_param {
+            type_: mpv_sys::mpv_render_param_type_MPV_RENDER_PARAM_INVALID,
+            data: ptr::null_mut(),
+        });
+        mpv_err(
+            (),
+            mpv_sys::mpv_render_context_render(self.ctx.as_ptr(), params.as_mut_ptr()),
+        )
+    }
+
+    #[inline]
+    /// Tell mpv that a frame was flipped, which helps with timing.
+    pub fn report_swap(&self) {
+        unsafe { mpv_sys::mpv_render_context_report_swap(self.ctx.as_ptr()) }
+    }
+}
+
+impl<'parent> Drop for RenderContext<'parent> {
+    fn drop(&mut self) {
+        unsafe { mpv_sys::mpv_render_context_free(self.ctx.as_ptr()) }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// The layout of the pixels rendered by a `SoftwareRenderContext`, 4 bytes each.
+pub enum PixelFormat {
+    /// Red, green, blue, then a padding byte in place of alpha.
+    Rgba32,
+    /// Blue, green, red, then a padding byte.
+    Bgrx32,
+}
+
+impl PixelFormat {
+    fn val(self) -> &'static [u8] {
+        match self {
+            PixelFormat::Rgba32 => b"rgb0\0",
+            PixelFormat::Bgrx32 => b"bgr0\0",
+        }
+    }
+}
+
+/// A context with which the video of an `Mpv` can be rendered into memory, see
+/// `Mpv::create_software_render_context`.
+pub struct SoftwareRenderContext<'parent> {
+    inner: RenderContext<'parent>,
+    format: PixelFormat,
+}
+
+impl<'parent> SoftwareRenderContext<'parent> {
+    #[inline]
+    /// The underlying `RenderContext`, e.g. to set the update callback.
+    pub fn render_context(&self) -> &RenderContext<'parent> {
+        &self.inner
+    }
+
+    #[inline]
+    /// The format pixels are rendered in.
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.format
+    }
+
+    #[inline]
+    /// Change the format pixels are rendered in.
+    pub fn set_pixel_format(&mut self, format: PixelFormat) {
+        self.format = format;
+    }
+
+    #[inline]
+    /// Render the video into `buf`, which holds `height` lines of `stride` bytes, each starting
+    /// with `width` pixels.
+    ///
+    /// Returns `Error::InvalidArgument` if `stride` is less than `width * 4`, or `buf` is shorter
+    /// than `stride * height`.
+    pub fn render(&self, stride: usize, buf: &mut [u8], width: u32, height: u32) -> Result<()> {
+        let mut size: [ctype::c_int; 2] = [
+            ctype::c_int::try_from(width).map_err(|_| Error::InvalidArgument)?,
+            ctype::c_int::try_from(height).map_err(|_| Error::InvalidArgument)?,
+        ];
+        let row = (width as usize).checked_mul(4);
+        let len = stride.checked_mul(height as usize);
+        if row.filter(|&row| stride >= row).is_none()
+            || len.filter(|&len| len <= buf.len()).is_none()
+        {
+            return Err(Error::InvalidArgument);
+        }
+        let mut stride = stride;
+
+        unsafe {
+            self.inner.render(&[
+                mpv_sys::mpv_render_param {
+                    type_: MPV_RENDER_PARAM_SW_SIZE,
+                    data: size.as_mut_ptr() as *mut _,
+                },
+                mpv_sys::mpv_render_param {
+                    type_: MPV_RENDER_PARAM_SW_FORMAT,
+                    data: self.format.val().as_ptr() as *mut _,
+                },
+                mpv_sys::mpv_render_param {
+                    type_: MPV_RENDER_PARAM_SW_STRIDE,
+                    data: &mut stride as *mut usize as *mut _,
+                },
+                mpv_sys::mpv_render_param {
+                    type_: MPV_RENDER_PARAM_SW_POINTER,
+                    data: buf.as_mut_ptr() as *mut _,
+                },
+            ])
+        }
+    }
+}
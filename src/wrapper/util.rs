@@ -0,0 +1,79 @@
+// Copyright (C) 2016  ParadoxSpiral
+//
+// This file is part of mpv-rs.
+//
+// This library is free software; you can redistribute it and/or
+// modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation; either
+// version 2.1 of the License, or (at your option) any later version.
+//
+// This library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+// Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public
+// License along with this library; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA  02110-1301  USA
+
+//! Helpers that work without an `Mpv` instance.
+
+/// Format a time in seconds like the OSD of mpv, i.e. `H:MM:SS`, or `MM:SS` if it's shorter than
+/// an hour. Fractional seconds are truncated.
+///
+/// Negative times are prefixed with `-`, and non-finite ones are formatted as `--:--`.
+pub fn format_time(seconds: f64) -> String {
+    if !seconds.is_finite() {
+        return "--:--".to_owned();
+    }
+
+    let sign = if seconds < 0. { "-" } else { "" };
+    let total = seconds.abs() as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}{}:{:02}:{:02}", sign, h, m, s)
+    } else {
+        format!("{}{:02}:{:02}", sign, m, s)
+    }
+}
+
+/// Parse a time in the `[-][[H:]M:]S[.frac]` format, e.g. `1:23` or `-0:05.5`, into seconds.
+///
+/// Minutes and seconds following another component must be less than 60.
+pub fn parse_time(time: &str) -> Option<f64> {
+    let (negative, time) = if let Some(time) = time.strip_prefix('-') {
+        (true, time)
+    } else {
+        (false, time)
+    };
+
+    let parts = time.split(':').collect::<Vec<_>>();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let (seconds, rest) = parts.split_last()?;
+    if seconds.is_empty() || !seconds.bytes().all(|c| c.is_ascii_digit() || c == b'.') {
+        return None;
+    }
+    let seconds = seconds.parse::<f64>().ok()?;
+    if !rest.is_empty() && seconds >= 60. {
+        return None;
+    }
+
+    let mut total: u64 = 0;
+    for (i, part) in rest.iter().enumerate() {
+        if part.is_empty() || !part.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let part = part.parse::<u64>().ok()?;
+        // The minutes of `H:M:S`.
+        if i > 0 && part >= 60 {
+            return None;
+        }
+        total = total.checked_mul(60)?.checked_add(part)?;
+    }
+
+    let total = total as f64 * 60. + seconds;
+    Some(if negative { -total } else { total })
+}